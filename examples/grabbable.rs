@@ -9,8 +9,8 @@ use stardust_xr_fusion::{
 	values::ResourceID,
 };
 use stardust_xr_molecules::{
-	DebugSettings, FrameSensitive, Grabbable, GrabbableSettings, PointerMode, UIElement,
-	VisualDebug,
+	DebugSettings, FrameSensitive, Grabbable, GrabbableSettings, InputGrabSettings, PointerMode,
+	UIElement, VisualDebug,
 };
 use tracing_subscriber::EnvFilter;
 use zbus::{conn::Builder, fdo::ObjectManager};
@@ -65,8 +65,11 @@ async fn main() {
 		Transform::identity(),
 		&field,
 		GrabbableSettings {
+			pointer: InputGrabSettings {
+				magnet: true,
+				..GrabbableSettings::default().pointer
+			},
 			pointer_mode: PointerMode::Move,
-			magnet: true,
 			..Default::default()
 		},
 	)
@@ -84,7 +87,7 @@ async fn main() {
 		.sync_event_loop(|client, _flow| {
 			grabbable.handle_events();
 			if grabbable.grab_action().actor_stopped() {
-				grabbable.set_pose([0.0; 3], glam::Quat::IDENTITY);
+				grabbable.set_pose([0.0; 3], glam::Quat::IDENTITY, 1.0);
 			}
 			while let Some(root_event) = client.get_root().recv_root_event() {
 				match root_event {
@@ -1,12 +1,13 @@
 use crate::{
 	UIElement, VisualDebug,
-	lines::{LineExt, circle, rounded_rectangle},
+	input_action::{Gesture, GestureSettings},
+	lines::{LineExt, circle, line_from_points, rounded_rectangle},
 	touch_plane::TouchPlane,
 };
 use glam::{Mat4, vec3};
 use map_range::MapRange;
 use stardust_xr_fusion::{
-	drawable::{Lines, LinesAspect},
+	drawable::{Line, Lines, LinesAspect},
 	node::NodeError,
 	spatial::{SpatialRefAspect, Transform},
 	values::{
@@ -34,20 +35,32 @@ impl Default for ButtonVisualSettings {
 pub struct ButtonSettings {
 	pub max_hover_distance: f32,
 	pub visuals: Option<ButtonVisualSettings>,
+	pub gestures: GestureSettings,
 }
 impl Default for ButtonSettings {
 	fn default() -> Self {
 		Self {
 			max_hover_distance: 0.025,
 			visuals: Some(ButtonVisualSettings::default()),
+			gestures: GestureSettings::default(),
 		}
 	}
 }
 
+/// How a toggle shows that it's on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToggleStyle {
+	/// Fill the outline in
+	Fill,
+	/// Always show the outline, with a check mark inside when on
+	Check,
+}
+
 pub struct Button {
 	settings: ButtonSettings,
 	touch_plane: TouchPlane,
 	visuals: Option<ButtonVisuals>,
+	toggled: Option<(ToggleStyle, bool)>,
 }
 impl Button {
 	pub fn create(
@@ -59,7 +72,7 @@ impl Button {
 		let size = size.into();
 		let half_size_x = size.x * 0.5;
 		let half_size_y = size.y * 0.5;
-		let touch_plane = TouchPlane::create(
+		let mut touch_plane = TouchPlane::create(
 			parent,
 			transform,
 			size,
@@ -67,6 +80,7 @@ impl Button {
 			-half_size_x..half_size_x,
 			half_size_y..-half_size_y,
 		)?;
+		touch_plane.gesture_settings = settings.gestures;
 
 		Ok(Button {
 			visuals: settings
@@ -75,12 +89,16 @@ impl Button {
 				.transpose()?,
 			settings,
 			touch_plane,
+			toggled: None,
 		})
 	}
 
 	pub fn touch_plane(&self) -> &TouchPlane {
 		&self.touch_plane
 	}
+	pub fn touch_plane_mut(&mut self) -> &mut TouchPlane {
+		&mut self.touch_plane
+	}
 
 	pub fn pressed(&self) -> bool {
 		!self.touch_plane.action().interact().current().is_empty()
@@ -91,15 +109,28 @@ impl Button {
 		self.touch_plane.action().interact().current().is_empty()
 			&& !self.touch_plane.action().interact().removed().is_empty()
 	}
+	/// Taps, long presses etc. on the button from the last update
+	pub fn gestures(&self) -> &[Gesture] {
+		self.touch_plane.gestures()
+	}
+
+	/// Show the button as switched on or off, None for a plain button
+	pub(crate) fn set_toggled(&mut self, toggled: Option<(ToggleStyle, bool)>) {
+		self.toggled = toggled;
+		self.update_visuals();
+	}
+	fn update_visuals(&self) {
+		if let Some(visuals) = &self.visuals {
+			visuals.update_toggled(&self.touch_plane, &self.settings, self.toggled);
+		}
+	}
 }
 impl UIElement for Button {
 	fn handle_events(&mut self) -> bool {
 		if !self.touch_plane.handle_events() {
 			return false;
 		}
-		if let Some(visuals) = &mut self.visuals {
-			visuals.update(&self.touch_plane, &self.settings);
-		}
+		self.update_visuals();
 		true
 	}
 }
@@ -140,6 +171,15 @@ impl ButtonVisuals {
 	}
 
 	pub fn update(&self, touch_plane: &TouchPlane, settings: &ButtonSettings) {
+		self.update_toggled(touch_plane, settings, None)
+	}
+	/// Like [`ButtonVisuals::update`], with the toggle's on/off indicator drawn inside the hover and press feedback
+	pub fn update_toggled(
+		&self,
+		touch_plane: &TouchPlane,
+		settings: &ButtonSettings,
+		toggled: Option<(ToggleStyle, bool)>,
+	) {
 		let closest_interaction = touch_plane
 			.action()
 			.hover()
@@ -162,18 +202,18 @@ impl ButtonVisuals {
 			self.segment_count / 4 - 1,
 		)
 		.thickness(self.visual_settings.line_thickness);
-		let _ = if let Some((interact_point, interact_distance)) = closest_interaction {
+		let mut lines = self.toggle_indicator(toggled);
+		if let Some((interact_point, interact_distance)) = closest_interaction {
 			// if we're touching the plane
 			if !touch_plane.action().interact().current().is_empty() {
 				// then fill the rectangle
-				let lines = vec![rounded_rectangle.color(self.visual_settings.accent_color)];
+				lines.push(rounded_rectangle.color(self.visual_settings.accent_color));
 				// create_unbounded_volume_signifiers(
 				// 	self.size,
 				// 	interact_distance,
 				// 	settings,
 				// 	&mut lines,
 				// );
-				self.lines.set_lines(&lines)
 			} else {
 				// if hovering
 				let blend = interact_distance
@@ -188,15 +228,57 @@ impl ButtonVisuals {
 					)));
 				circle.points.reverse();
 
-				self.lines.set_lines(&[circle
-					.clone()
-					.lerp(&rounded_rectangle, blend)
-					.unwrap_or_default()])
+				lines.push(
+					circle
+						.clone()
+						.lerp(&rounded_rectangle, blend)
+						.unwrap_or_default(),
+				);
 			}
-		} else {
-			// then nothing is in range
-			self.lines.set_lines(&[])
+		}
+		let _ = self.lines.set_lines(&lines);
+	}
+
+	/// Inset so it never hides the hover and press feedback around the edge
+	fn toggle_indicator(&self, toggled: Option<(ToggleStyle, bool)>) -> Vec<Line> {
+		let Some((style, on)) = toggled else {
+			return Vec::new();
 		};
+		let inset = rounded_rectangle(
+			self.size.x * 0.8,
+			self.size.y * 0.8,
+			self.visual_settings.line_thickness * 0.5,
+			self.segment_count / 4 - 1,
+		)
+		.thickness(self.visual_settings.line_thickness);
+		let accent = self.visual_settings.accent_color;
+		match (style, on) {
+			(ToggleStyle::Fill, true) => vec![inset.color(accent)],
+			(ToggleStyle::Fill, false) => Vec::new(),
+			(ToggleStyle::Check, on) => {
+				// the box is always there, dimmer so the check mark stands out
+				let mut lines = vec![inset.color(rgba_linear!(
+					accent.c.r,
+					accent.c.g,
+					accent.c.b,
+					accent.a * 0.5
+				))];
+				if on {
+					lines.push(self.check_mark());
+				}
+				lines
+			}
+		}
+	}
+	fn check_mark(&self) -> Line {
+		let size = self.size.x.min(self.size.y);
+		line_from_points(vec![
+			vec3(-0.3 * size, 0.0, 0.0),
+			vec3(-0.1 * size, -0.2 * size, 0.0),
+			vec3(0.3 * size, 0.2 * size, 0.0),
+		])
+		.color(self.visual_settings.accent_color)
+		.thickness(self.visual_settings.line_thickness)
 	}
 }
 
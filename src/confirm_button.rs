@@ -0,0 +1,152 @@
+use crate::{
+	Exposure, FrameSensitive, UIElement, VisualDebug,
+	button::{Button, ButtonSettings},
+	lines::{LineExt, arc_inclusive, circle},
+	touch_plane::TouchPlane,
+};
+use glam::Mat4;
+use stardust_xr_fusion::{
+	drawable::{Lines, LinesAspect},
+	node::NodeError,
+	root::FrameInfo,
+	spatial::{SpatialRefAspect, Transform},
+	values::Vector2,
+};
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+#[derive(Debug, Clone, Copy)]
+pub struct ConfirmButtonSettings {
+	pub button: ButtonSettings,
+	/// Seconds it has to be held to confirm
+	pub hold_time: f32,
+	/// How many seconds of holding are lost per second after letting go
+	pub cooling: f32,
+}
+impl Default for ConfirmButtonSettings {
+	fn default() -> Self {
+		Self {
+			button: ButtonSettings::default(),
+			hold_time: 1.0,
+			cooling: 2.0,
+		}
+	}
+}
+
+/// A button that has to be held down for a while before it fires, for destructive actions like closing or derezzing.
+///
+/// Needs [`FrameSensitive::frame`] to be called every frame to heat up and cool down.
+pub struct ConfirmButton {
+	settings: ConfirmButtonSettings,
+	button: Button,
+	exposure: Exposure,
+	ring: Option<Lines>,
+	ring_radius: f32,
+	/// Fired and waiting to be released before it can fire again
+	fired: bool,
+	confirmed: bool,
+}
+impl ConfirmButton {
+	pub fn create(
+		parent: &impl SpatialRefAspect,
+		transform: Transform,
+		size: impl Into<Vector2<f32>>,
+		settings: ConfirmButtonSettings,
+	) -> Result<Self, NodeError> {
+		let size = size.into();
+		let button = Button::create(parent, transform, size, settings.button)?;
+		let ring = settings
+			.button
+			.visuals
+			.map(|_| Lines::create(button.touch_plane().root(), Transform::identity(), &[]))
+			.transpose()?;
+		Ok(ConfirmButton {
+			settings,
+			button,
+			exposure: Exposure {
+				exposure: 0.0,
+				cooling: settings.cooling,
+				max: settings.hold_time,
+			},
+			ring,
+			ring_radius: size.x.min(size.y) * 0.5,
+			fired: false,
+			confirmed: false,
+		})
+	}
+
+	/// True for the frame the button was held long enough
+	pub fn confirmed(&self) -> bool {
+		self.confirmed
+	}
+	/// How far along the hold is, from 0 to 1
+	pub fn progress(&self) -> f32 {
+		if self.exposure.max <= 0.0 {
+			return 1.0;
+		}
+		(self.exposure.exposure / self.exposure.max).clamp(0.0, 1.0)
+	}
+	pub fn button(&self) -> &Button {
+		&self.button
+	}
+	pub fn touch_plane(&self) -> &TouchPlane {
+		self.button.touch_plane()
+	}
+
+	fn update_ring(&self) {
+		let (Some(ring), Some(visuals)) = (&self.ring, self.settings.button.visuals) else {
+			return;
+		};
+		let progress = self.progress();
+		if progress <= 0.0 {
+			let _ = ring.set_lines(&[]);
+			return;
+		}
+		// arcs are drawn on the XZ plane starting at +Z, so this turns it to start at the top of the button
+		let line = if progress >= 1.0 {
+			circle(64, 0.0, self.ring_radius)
+		} else {
+			let segments = ((64.0 * progress).ceil() as usize).max(1);
+			arc_inclusive(segments, 0.0, TAU * progress, self.ring_radius)
+		}
+		.transform(Mat4::from_rotation_x(-FRAC_PI_2))
+		.thickness(visuals.line_thickness)
+		.color(visuals.accent_color);
+		let _ = ring.set_lines(&[line]);
+	}
+}
+impl UIElement for ConfirmButton {
+	fn handle_events(&mut self) -> bool {
+		self.button.handle_events()
+	}
+}
+impl FrameSensitive for ConfirmButton {
+	fn frame(&mut self, info: &FrameInfo) {
+		self.confirmed = false;
+		self.exposure.cooling = self.settings.cooling;
+		self.exposure.max = self.settings.hold_time;
+
+		let held = !self
+			.button
+			.touch_plane()
+			.action()
+			.interact()
+			.current()
+			.is_empty();
+		if !held {
+			self.fired = false;
+			self.exposure.update(info.delta);
+		} else if !self.fired {
+			self.exposure.expose(1.0, info.delta);
+			if self.exposure.exposure >= self.exposure.max {
+				self.confirmed = true;
+				self.fired = true;
+			}
+		}
+		self.update_ring();
+	}
+}
+impl VisualDebug for ConfirmButton {
+	fn set_debug(&mut self, settings: Option<crate::DebugSettings>) {
+		self.button.set_debug(settings)
+	}
+}
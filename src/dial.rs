@@ -0,0 +1,394 @@
+use crate::{
+	DebugSettings, UIElement, VisualDebug,
+	input_action::{
+		InputQueue, InputQueueable, InteractCondition, InteractConditionSettings, SingleAction,
+	},
+	lines::{self, LineExt},
+};
+use glam::{Mat4, Quat, Vec3, vec3};
+use stardust_xr_fusion::{
+	drawable::{Lines, LinesAspect},
+	fields::{CylinderShape, Field, Shape, TorusShape},
+	input::{Hand, InputData, InputDataType, InputHandler},
+	node::NodeError,
+	spatial::{Spatial, SpatialRefAspect, Transform},
+	values::color::{Rgba, color_space::LinearRgb, rgba_linear},
+};
+use std::f32::consts::{FRAC_PI_4, PI, TAU};
+
+/// The dial turns around its local Y axis.
+#[derive(Debug, Clone, Copy)]
+pub enum DialShape {
+	/// A solid knob
+	Cylinder { height: f32 },
+	/// A ring you grab by the rim
+	Torus { thickness: f32 },
+}
+impl DialShape {
+	fn field_shape(self, radius: f32) -> Shape {
+		match self {
+			DialShape::Cylinder { height } => Shape::Cylinder(CylinderShape {
+				length: height,
+				radius,
+			}),
+			DialShape::Torus { thickness } => Shape::Torus(TorusShape {
+				radius_a: radius,
+				radius_b: thickness * 0.5,
+			}),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DialVisualSettings {
+	pub line_thickness: f32,
+	pub track_color: Rgba<f32, LinearRgb>,
+	pub accent_color: Rgba<f32, LinearRgb>,
+}
+impl Default for DialVisualSettings {
+	fn default() -> Self {
+		Self {
+			line_thickness: 0.005,
+			track_color: rgba_linear!(0.5, 0.5, 0.5, 1.0),
+			accent_color: rgba_linear!(0.0, 1.0, 0.75, 1.0),
+		}
+	}
+}
+
+/// Angles are in radians around the Y axis, starting at +Z.
+#[derive(Debug, Clone, Copy)]
+pub struct DialSettings {
+	pub shape: DialShape,
+	pub min: f32,
+	pub max: f32,
+	/// None means the dial turns smoothly, otherwise it clicks into multiples of this angle from `min`
+	pub detent: Option<f32>,
+	pub default: f32,
+	pub max_distance: f32,
+	pub interact_condition: InteractConditionSettings,
+	/// Radians per unit of `scroll_continuous`
+	pub continuous_gain: f32,
+	/// Radians per `scroll_discrete` step, the detent is used instead if there is one
+	pub discrete_gain: f32,
+	pub visuals: Option<DialVisualSettings>,
+}
+impl Default for DialSettings {
+	fn default() -> Self {
+		Self {
+			shape: DialShape::Cylinder { height: 0.02 },
+			min: -PI * 0.75,
+			max: PI * 0.75,
+			detent: None,
+			default: -PI * 0.75,
+			max_distance: 0.025,
+			interact_condition: InteractConditionSettings::grab(),
+			continuous_gain: 0.01,
+			discrete_gain: FRAC_PI_4 * 0.25,
+			visuals: Some(DialVisualSettings::default()),
+		}
+	}
+}
+impl DialSettings {
+	/// Clamp to min and max and click into the nearest detent
+	pub fn snap(&self, angle: f32) -> f32 {
+		let (low, high) = (self.min.min(self.max), self.min.max(self.max));
+		let angle = match self.detent.filter(|detent| *detent > 0.0) {
+			Some(detent) => self.min + ((angle - self.min) / detent).round() * detent,
+			None => angle,
+		};
+		angle.clamp(low, high)
+	}
+}
+
+/// Twist with your hand, pinch and drag around the rim, or scroll with a pointer to turn it.
+pub struct Dial {
+	settings: DialSettings,
+	radius: f32,
+	root: Spatial,
+	field: Field,
+	input: InputQueue,
+	interact_condition: InteractCondition,
+	interact: SingleAction,
+	/// Unsnapped angle while turning, so detents don't eat small movements
+	raw_angle: f32,
+	angle: f32,
+	previous_grip: Option<(GripMode, f32)>,
+	changed: bool,
+	lines: Option<Lines>,
+	debug_lines: Option<Lines>,
+}
+impl Dial {
+	pub fn create(
+		parent: &impl SpatialRefAspect,
+		transform: Transform,
+		radius: f32,
+		settings: DialSettings,
+	) -> Result<Self, NodeError> {
+		let root = Spatial::create(parent, transform)?;
+		let field = Field::create(
+			&root,
+			Transform::identity(),
+			settings.shape.field_shape(radius),
+		)?;
+		let input = InputHandler::create(&root, Transform::identity(), &field)?.queue()?;
+		let lines = settings
+			.visuals
+			.map(|_| Lines::create(&root, Transform::identity(), &[]))
+			.transpose()?;
+		let angle = settings.snap(settings.default);
+		let dial = Dial {
+			settings,
+			radius,
+			root,
+			field,
+			input,
+			interact_condition: InteractCondition::default(),
+			interact: SingleAction::default(),
+			raw_angle: angle,
+			angle,
+			previous_grip: None,
+			changed: false,
+			lines,
+			debug_lines: None,
+		};
+		dial.update_visuals();
+		Ok(dial)
+	}
+
+	pub fn angle(&self) -> f32 {
+		self.angle
+	}
+	/// The angle from min to max as 0 to 1
+	pub fn value(&self) -> f32 {
+		let range = self.settings.max - self.settings.min;
+		if range == 0.0 {
+			return 0.0;
+		}
+		(self.angle - self.settings.min) / range
+	}
+	/// Doesn't count as a change
+	pub fn set_angle(&mut self, angle: f32) {
+		self.angle = self.settings.snap(angle);
+		self.raw_angle = self.angle;
+		self.update_visuals();
+	}
+	/// The new angle if it changed during the last update, [`UIElement::handle_events`] only says whether input was handled
+	pub fn changed(&self) -> Option<f32> {
+		self.changed.then_some(self.angle)
+	}
+
+	pub fn root(&self) -> &Spatial {
+		&self.root
+	}
+	pub fn field(&self) -> &Field {
+		&self.field
+	}
+	pub fn input_queue(&self) -> &InputQueue {
+		&self.input
+	}
+	/// Mainly useful for recording or replaying input
+	pub fn input_queue_mut(&mut self) -> &mut InputQueue {
+		&mut self.input
+	}
+	pub fn interact_action(&self) -> &SingleAction {
+		&self.interact
+	}
+
+	/// Decided once per grab so the grip angle never jumps between the two ways of measuring it
+	fn grip_mode(&self, input: &InputData) -> GripMode {
+		let InputDataType::Hand(h) = &input.input else {
+			return GripMode::Drag;
+		};
+		// pinching near the middle means twisting the knob, out by the rim means dragging it around
+		let pinch = pinch_point(h);
+		if pinch.x.hypot(pinch.z) <= self.radius * 0.5 {
+			GripMode::Twist
+		} else {
+			GripMode::Drag
+		}
+	}
+	/// Angle of the grip around the Y axis, deltas between updates turn the dial
+	fn grip_angle(&self, input: &InputData, mode: GripMode) -> f32 {
+		match &input.input {
+			InputDataType::Hand(h) => match mode {
+				GripMode::Twist => twist_angle(h.palm.rotation.into()),
+				GripMode::Drag => point_angle(pinch_point(h)),
+			},
+			InputDataType::Pointer(p) => {
+				let origin = Vec3::from(p.origin);
+				let direction = Vec3::from(p.direction());
+				// edge on, the ray never hits the dial's plane
+				if direction.y.abs() < f32::EPSILON {
+					return point_angle(origin);
+				}
+				let t = -origin.y / direction.y;
+				point_angle(origin + direction * t)
+			}
+			InputDataType::Tip(t) => point_angle(t.origin.into()),
+		}
+	}
+	fn turn(&mut self, delta: f32) {
+		self.raw_angle = (self.raw_angle + delta).clamp(
+			self.settings.min.min(self.settings.max),
+			self.settings.min.max(self.settings.max),
+		);
+		let angle = self.settings.snap(self.raw_angle);
+		if angle != self.angle {
+			self.angle = angle;
+			self.changed = true;
+		}
+	}
+
+	fn update_visuals(&self) {
+		let (Some(lines), Some(visuals)) = (&self.lines, self.settings.visuals) else {
+			return;
+		};
+		let radius = match self.settings.shape {
+			DialShape::Cylinder { .. } => self.radius,
+			DialShape::Torus { thickness } => self.radius + thickness * 0.5,
+		} + visuals.line_thickness * 2.0;
+		let height = match self.settings.shape {
+			DialShape::Cylinder { height } => height * 0.5,
+			DialShape::Torus { thickness } => thickness * 0.5,
+		};
+		let segments = |start: f32, end: f32| ((32.0 * (end - start).abs() / TAU) as usize).max(1);
+		let (min, max) = (self.settings.min, self.settings.max);
+		let filled = lines::arc_inclusive(segments(min, self.angle), min, self.angle, radius)
+			.color(visuals.accent_color);
+		let track = lines::arc_inclusive(segments(self.angle, max), self.angle, max, radius)
+			.color(visuals.track_color);
+		// a tick on the rim pointing at the current angle
+		let direction = vec3(self.angle.sin(), 0.0, self.angle.cos());
+		let tick = lines::line_from_points(vec![
+			direction * (radius - visuals.line_thickness * 4.0),
+			direction * radius,
+		])
+		.color(visuals.accent_color);
+		let _ = lines.set_lines(&[filled, track, tick].map(|line| {
+			line.thickness(visuals.line_thickness)
+				.transform(Mat4::from_translation(Vec3::Y * height))
+		}));
+	}
+}
+impl UIElement for Dial {
+	fn handle_events(&mut self) -> bool {
+		self.changed = false;
+		// dials don't get frame events, so check the hold time whenever they're handled
+		let hold_elapsed = self
+			.interact_condition
+			.update_hold(&self.settings.interact_condition);
+		if !self.input.handle_events() && !hold_elapsed {
+			return false;
+		}
+		self.interact_condition
+			.update(&self.input, &self.settings.interact_condition);
+		let max_distance = self.settings.max_distance;
+		self.interact.update(
+			false,
+			&self.input,
+			|input| match &input.input {
+				InputDataType::Hand(h) => {
+					h.thumb.tip.distance < max_distance && h.index.tip.distance < max_distance
+				}
+				_ => input.distance < max_distance,
+			},
+			self.interact_condition.condition(),
+		);
+
+		if let Some(actor) = self.interact.actor().cloned() {
+			match self
+				.previous_grip
+				.filter(|_| !self.interact.actor_started())
+			{
+				Some((mode, previous)) => {
+					let grip = self.grip_angle(&actor, mode);
+					self.turn(wrap_angle(grip - previous));
+					self.previous_grip = Some((mode, grip));
+				}
+				None => {
+					let mode = self.grip_mode(&actor);
+					self.previous_grip = Some((mode, self.grip_angle(&actor, mode)));
+				}
+			}
+		} else {
+			self.previous_grip = None;
+			self.raw_angle = self.angle;
+		}
+
+		let discrete_gain = self.settings.detent.unwrap_or(self.settings.discrete_gain);
+		let scroll = self
+			.interact
+			.hovering()
+			.current()
+			.iter()
+			.filter(|input| matches!(input.input, InputDataType::Pointer(_)))
+			.map(|input| {
+				input.datamap.with_data(|datamap| {
+					datamap.idx("scroll_continuous").as_vector().idx(1).as_f32()
+						* self.settings.continuous_gain
+						+ datamap.idx("scroll_discrete").as_vector().idx(1).as_f32() * discrete_gain
+				})
+			})
+			.sum::<f32>();
+		if scroll != 0.0 {
+			// scrolling steps straight from detent to detent
+			self.raw_angle = self.angle;
+			self.turn(scroll);
+			self.raw_angle = self.angle;
+		}
+
+		if self.changed {
+			self.update_visuals();
+		}
+		true
+	}
+}
+impl VisualDebug for Dial {
+	fn set_debug(&mut self, settings: Option<DebugSettings>) {
+		self.debug_lines = settings.and_then(|settings| {
+			let lines = lines::shape(self.settings.shape.field_shape(self.radius))
+				.into_iter()
+				.map(|line| {
+					line.thickness(settings.line_thickness)
+						.color(settings.line_color)
+				})
+				.collect::<Vec<_>>();
+			Lines::create(&self.root, Transform::identity(), &lines).ok()
+		})
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GripMode {
+	Twist,
+	Drag,
+}
+
+fn pinch_point(hand: &Hand) -> Vec3 {
+	(Vec3::from(hand.thumb.tip.position) + Vec3::from(hand.index.tip.position)) * 0.5
+}
+/// Angle around the Y axis starting at +Z, matching [`lines::arc`]
+fn point_angle(point: Vec3) -> f32 {
+	point.x.atan2(point.z)
+}
+/// How far a rotation twists around the Y axis
+fn twist_angle(rotation: Quat) -> f32 {
+	2.0 * rotation.y.atan2(rotation.w)
+}
+fn wrap_angle(angle: f32) -> f32 {
+	(angle + PI).rem_euclid(TAU) - PI
+}
+
+#[test]
+fn dial_snap() {
+	let settings = DialSettings {
+		min: 0.0,
+		max: PI,
+		detent: Some(FRAC_PI_4),
+		..Default::default()
+	};
+	assert_eq!(settings.snap(0.3), 0.0);
+	assert_eq!(settings.snap(0.5), FRAC_PI_4);
+	assert_eq!(settings.snap(TAU), PI);
+	assert!((wrap_angle(TAU - 0.1) + 0.1).abs() < 0.0001);
+}
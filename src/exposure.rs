@@ -16,9 +16,9 @@ impl Exposure {
 		self.exposure = self.exposure.clamp(0.0, self.max);
 	}
 	pub fn expose_flash(&mut self, amount: f32) {
-		self.exposure += amount;
+		self.exposure = (self.exposure + amount).clamp(0.0, self.max);
 	}
 	pub fn expose(&mut self, amount: f32, delta: f32) {
-		self.exposure += amount * delta;
+		self.exposure = (self.exposure + amount * delta).clamp(0.0, self.max);
 	}
 }
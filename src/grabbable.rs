@@ -1,14 +1,39 @@
+mod constraints;
+pub use constraints::*;
+mod snap;
+pub use snap::*;
+mod events;
+pub use events::*;
+mod state;
+pub use state::*;
+mod follow;
+pub use follow::*;
+mod throw;
+pub use throw::*;
+mod history;
+pub use history::*;
+mod hand_off;
+pub use hand_off::*;
+mod collision;
+pub use collision::*;
+
 use crate::{
-	FrameSensitive, UIElement, VisualDebug,
-	input_action::{InputQueue, InputQueueable, SingleAction, grab_pinch_interact},
-	lines::{LineExt, axes, bounding_box},
-	reparentable::{ReparentTransformReceiver, Reparentable},
+	DebugSettings, FrameSensitive, UIElement, VisualDebug,
+	dbus::{AbortOnDrop, DbusObjectHandle, DbusObjectHandles},
+	input_action::{
+		DualAction, DualTransform, InputQueue, InputQueueable, InteractCondition,
+		InteractConditionSettings, SingleAction,
+	},
+	lines::{LineExt, axes, bounding_box, line_from_points},
+	persistence::History,
+	reparentable::{ReparentTarget, ReparentTransformReceiver, Reparentable},
+	zone::Zone,
 };
-use glam::{Affine3A, Quat, Vec3, vec3};
+use glam::{Affine3A, Mat4, Quat, Vec3, vec3};
 use stardust_xr_fusion::{
 	drawable::{Lines, LinesAspect},
 	fields::{Field, FieldRefAspect},
-	input::{InputDataType, InputHandler},
+	input::{InputData, InputDataType, InputHandler},
 	node::{NodeError, NodeType},
 	root::FrameInfo,
 	spatial::{Spatial, SpatialAspect, SpatialRef, SpatialRefAspect, Transform},
@@ -16,17 +41,27 @@ use stardust_xr_fusion::{
 };
 use std::{
 	f32::consts::PI,
+	marker::PhantomData,
 	path::{Path, PathBuf},
+	sync::Arc,
 };
 use tokio::sync::mpsc;
 use tracing::{debug, trace};
-use zbus::Connection;
+use zbus::{Connection, zvariant::OwnedObjectPath};
 
 fn swing_direction(direction: Vec3) -> Quat {
 	let pitch = direction.y.asin();
 	let yaw = direction.z.atan2(direction.x);
 	Quat::from_rotation_y(-yaw - PI / 2.0) * Quat::from_rotation_x(pitch)
 }
+fn grab_pose(input: &InputData) -> Affine3A {
+	let (rotation, position) = match &input.input {
+		InputDataType::Pointer(p) => (p.orientation.into(), p.origin.into()),
+		InputDataType::Hand(h) => (h.palm.rotation.into(), h.palm.position.into()),
+		InputDataType::Tip(t) => (t.orientation.into(), t.origin.into()),
+	};
+	Affine3A::from_rotation_translation(rotation, position)
+}
 
 /// How should the grabbable interact with pointers?
 #[derive(Debug, Clone, Copy)]
@@ -48,9 +83,25 @@ pub struct MomentumSettings {
 	pub threshold: f32,
 }
 
+/// Scale limits for grabbing with a second hand.
 #[derive(Debug, Clone, Copy)]
-pub struct GrabbableSettings {
-	/// Max distance that you can be to start grabbing
+pub struct TwoHandedSettings {
+	pub min_scale: f32,
+	pub max_scale: f32,
+}
+impl Default for TwoHandedSettings {
+	fn default() -> Self {
+		Self {
+			min_scale: 0.1,
+			max_scale: 10.0,
+		}
+	}
+}
+
+/// How grabbing behaves for a single type of input.
+#[derive(Debug, Clone, Copy)]
+pub struct InputGrabSettings {
+	/// Max distance that you can be to start grabbing, for hands both the thumb and index tip have to be this close
 	pub max_distance: f32,
 	/// None means no linear momentum.
 	pub linear_momentum: Option<MomentumSettings>,
@@ -58,12 +109,10 @@ pub struct GrabbableSettings {
 	pub angular_momentum: Option<MomentumSettings>,
 	/// Should the grabbable be magnetized to the grab point?
 	pub magnet: bool,
-	/// How should pointers be handled?
-	pub pointer_mode: PointerMode,
-	/// Should the object be reparentable?
-	pub reparentable: bool,
+	/// Meters to push or pull the grabbable per unit of scrolling, 0 disables it
+	pub scroll_gain: f32,
 }
-impl Default for GrabbableSettings {
+impl Default for InputGrabSettings {
 	fn default() -> Self {
 		Self {
 			max_distance: 0.05,
@@ -76,8 +125,68 @@ impl Default for GrabbableSettings {
 				threshold: 0.2,
 			}),
 			magnet: true,
+			scroll_gain: 0.0,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GrabbableSettings {
+	pub hand: InputGrabSettings,
+	pub tip: InputGrabSettings,
+	pub pointer: InputGrabSettings,
+	/// How should pointers be handled?
+	pub pointer_mode: PointerMode,
+	/// Should the object be reparentable?
+	pub reparentable: bool,
+	/// When hands, pointers and tips count as grabbing
+	pub grab_condition: InteractConditionSettings,
+	/// What happens when a second input grabs it
+	pub hand_off: HandOffSettings,
+	/// Limits on movement and rotation, in the content space
+	pub constraints: GrabbableConstraints,
+	/// Grid, angle and upright snapping, shown with the debug lines
+	pub snap: SnapSettings,
+	/// None means the grabbable sticks rigidly to the input, otherwise it trails behind on springs
+	pub follow: Option<FollowSettings>,
+	/// Steer thrown grabbables into the zone they're predicted to land in, see [`Grabbable::set_throw_targets`]
+	pub throw_assist: bool,
+	/// How many moves and reparents can be undone, 0 disables undo.
+	/// Undo is also served over DBus at the grabbable's path once this is above 0.
+	pub undo_history: usize,
+	/// None means momentum goes straight through everything, otherwise it bounces off [`Grabbable::set_colliders`]
+	pub collision: Option<CollisionSettings>,
+}
+impl Default for GrabbableSettings {
+	fn default() -> Self {
+		Self {
+			hand: InputGrabSettings::default(),
+			tip: InputGrabSettings::default(),
+			pointer: InputGrabSettings {
+				// pointers are just too unstable to magnet
+				magnet: false,
+				scroll_gain: 0.01,
+				..Default::default()
+			},
 			pointer_mode: PointerMode::Parent,
 			reparentable: true,
+			grab_condition: InteractConditionSettings::grab(),
+			hand_off: HandOffSettings::default(),
+			constraints: GrabbableConstraints::default(),
+			snap: SnapSettings::default(),
+			follow: None,
+			throw_assist: false,
+			undo_history: 0,
+			collision: None,
+		}
+	}
+}
+impl GrabbableSettings {
+	pub fn input(&self, input: &InputDataType) -> &InputGrabSettings {
+		match input {
+			InputDataType::Hand(_) => &self.hand,
+			InputDataType::Pointer(_) => &self.pointer,
+			InputDataType::Tip(_) => &self.tip,
 		}
 	}
 }
@@ -89,24 +198,55 @@ pub struct Grabbable {
 	content_parent: Spatial,
 	field: Field,
 	input: InputQueue,
+	grab_condition: InteractCondition,
+	/// An input was held long enough to grab between events
+	hold_elapsed: bool,
 	grab_action: SingleAction,
+	dual_action: DualAction,
+	dual_start_pose: Affine3A,
+	hand_off_blend: Option<HandOffBlend>,
 
 	content_lines: Lines,
 	root_lines: Lines,
+	debug: Option<DebugSettings>,
 	pub settings: GrabbableSettings,
 
 	prev_pose: Affine3A,
 	relative_transform: Affine3A, // Relative transform matrix during grab
 	pose: Affine3A,
+	target_pose: Affine3A,
+	spring: SpringFollow,
 
 	closest_point_tx: mpsc::Sender<Vec3>,
 	closest_point_rx: mpsc::Receiver<Vec3>,
 
+	colliders: Arc<[Field]>,
+	contacts_tx: mpsc::Sender<Vec<Contact>>,
+	contacts_rx: mpsc::Receiver<Vec<Contact>>,
+	contacts_pending: bool,
+
+	throw_targets: Arc<[Arc<Zone>]>,
+	throw_target_tx: mpsc::Sender<Vec3>,
+	throw_target_rx: mpsc::Receiver<Vec3>,
+
 	linear_velocity: Option<Vec3>,
+	/// Where steered momentum is easing towards
+	steer_target: Option<Vec3>,
 	angular_velocity: Option<(Vec3, f32)>,
 
 	waiting_for_transform: bool,
 	transform_changed: Option<ReparentTransformReceiver>,
+	/// Settings for the type of input that grabbed it last, momentum keeps using them after it's released
+	grab_input_settings: InputGrabSettings,
+
+	events: GrabbableEvents,
+	was_reparented: bool,
+	/// Pose at the last [`GrabbableEvent::Moved`]
+	reported_pose: Affine3A,
+
+	history: History<PoseEntry>,
+	history_rx: Option<mpsc::Receiver<HistoryCommand>>,
+	_history_handles: Option<DbusObjectHandles>,
 }
 impl Grabbable {
 	pub fn create(
@@ -123,35 +263,116 @@ impl Grabbable {
 		let content_lines = Lines::create(&content_parent, Transform::identity(), &[])?;
 		let root_lines = Lines::create(content_space, Transform::identity(), &[])?;
 		let (closest_point_tx, closest_point_rx) = mpsc::channel(1);
+		let (throw_target_tx, throw_target_rx) = mpsc::channel(1);
+		let (contacts_tx, contacts_rx) = mpsc::channel(1);
 		let mut grabbable = Grabbable {
 			reparentable: None,
 			connection,
 			path: path.as_ref().to_path_buf(),
 			content_parent,
 			input,
+			grab_condition: InteractCondition::default(),
+			hold_elapsed: false,
 			grab_action: SingleAction::default(),
+			dual_action: DualAction::default(),
+			dual_start_pose: Affine3A::IDENTITY,
+			hand_off_blend: None,
 			field: field.clone(),
 
 			content_lines,
 			root_lines,
+			debug: None,
 			settings,
 
 			prev_pose: Affine3A::IDENTITY,
 			relative_transform: Affine3A::IDENTITY,
 			pose: Affine3A::IDENTITY,
+			target_pose: Affine3A::IDENTITY,
+			spring: SpringFollow::default(),
 
 			closest_point_tx,
 			closest_point_rx,
 
+			colliders: Arc::new([]),
+			contacts_tx,
+			contacts_rx,
+			contacts_pending: false,
+
+			throw_targets: Arc::new([]),
+			throw_target_tx,
+			throw_target_rx,
+
 			linear_velocity: None,
+			steer_target: None,
 			angular_velocity: None,
 
 			waiting_for_transform: false,
 			transform_changed: None,
+			grab_input_settings: settings.hand,
+
+			events: GrabbableEvents::default(),
+			was_reparented: false,
+			reported_pose: Affine3A::IDENTITY,
+
+			history: History::new(PoseEntry {
+				pose: Affine3A::IDENTITY,
+				parent: None,
+			}),
+			history_rx: None,
+			_history_handles: None,
 		};
+		if settings.undo_history > 0 {
+			grabbable.serve_history();
+		}
 		grabbable.make_reparentable();
 		Ok(grabbable)
 	}
+	/// Create a grabbable where it was when `state` was saved, e.g. from the client's saved state.
+	pub fn create_with_state(
+		connection: Connection,
+		path: impl AsRef<Path>,
+		content_space: &impl SpatialRefAspect,
+		field: &Field,
+		settings: GrabbableSettings,
+		state: &GrabbableState,
+	) -> Result<Self, NodeError> {
+		let mut grabbable = Self::create(
+			connection,
+			path,
+			content_space,
+			Transform::identity(),
+			field,
+			settings,
+		)?;
+		grabbable.restore_state(state);
+		Ok(grabbable)
+	}
+	fn serve_history(&mut self) {
+		let Some(path) = self
+			.path
+			.to_str()
+			.and_then(|path| OwnedObjectPath::try_from(path).ok())
+		else {
+			return;
+		};
+		let (history_tx, history_rx) = mpsc::channel(6);
+		let abort_handle = tokio::spawn({
+			let connection = self.connection.clone();
+			let path = path.clone();
+			async move {
+				let _ = connection
+					.object_server()
+					.at(path, PoseHistoryInner(history_tx))
+					.await;
+			}
+		})
+		.abort_handle();
+		self.history_rx = Some(history_rx);
+		self._history_handles = Some(DbusObjectHandles(Box::new((
+			AbortOnDrop(abort_handle),
+			DbusObjectHandle::<PoseHistoryInner>(self.connection.clone(), path, PhantomData),
+		))));
+	}
 	fn make_reparentable(&mut self) {
 		self.reparentable = self
 			.settings
@@ -171,11 +392,36 @@ impl Grabbable {
 	}
 	const LINEAR_VELOCITY_STOP_THRESHOLD: f32 = 0.001;
 	fn apply_linear_momentum(&mut self, info: &FrameInfo, settings: MomentumSettings) {
+		self.collide(info.delta);
 		let Some(velocity) = &mut self.linear_velocity else {
+			self.steer_target.take();
 			return;
 		};
 		let delta = info.delta;
-		if velocity.length_squared() < Self::LINEAR_VELOCITY_STOP_THRESHOLD {
+		if let Some(target) = self.steer_target {
+			match steer_step(
+				self.pose.translation.into(),
+				target,
+				&self.settings.constraints,
+				settings.drag,
+				delta,
+			) {
+				SteerStep::Moving {
+					position,
+					velocity: steer_velocity,
+				} => {
+					*velocity = steer_velocity;
+					self.pose.translation = position.into();
+					trace!(?target, "steering momentum");
+				}
+				SteerStep::Arrived(position) => {
+					self.pose.translation = position.into();
+					self.linear_velocity.take();
+					self.steer_target.take();
+					self.make_reparentable();
+				}
+			}
+		} else if velocity.length_squared() < Self::LINEAR_VELOCITY_STOP_THRESHOLD {
 			self.linear_velocity.take();
 
 			// lets us slide the grabbable into a zone seamlessly
@@ -186,6 +432,51 @@ impl Grabbable {
 			trace!(?velocity, "linear momentum");
 		}
 	}
+	/// Bounce off the latest contacts and ask for the next ones
+	fn collide(&mut self, delta: f32) {
+		let Some(collision) = self.settings.collision else {
+			return;
+		};
+		// read these even without momentum so old contacts don't linger until the next throw
+		let contacts = self.contacts_rx.try_recv().ok();
+		if contacts.is_some() {
+			self.contacts_pending = false;
+		}
+		let Some(mut velocity) = self.linear_velocity else {
+			return;
+		};
+		if let Some(contacts) = contacts {
+			let mut position = Vec3::from(self.pose.translation);
+			for contact in contacts {
+				if let Some((new_position, new_velocity)) =
+					collision.respond(&contact, position, velocity, delta)
+				{
+					trace!(?contact, "momentum collision");
+					// bouncing off something means it isn't going to the steering target anymore
+					self.steer_target = None;
+					position = new_position;
+					velocity = new_velocity;
+				}
+			}
+			self.pose.translation = position.into();
+			self.linear_velocity.replace(velocity);
+		}
+		if !self.contacts_pending && !self.colliders.is_empty() {
+			self.contacts_pending = true;
+			let colliders = self.colliders.clone();
+			let space = self.input.handler().clone();
+			let point = Vec3::from(self.pose.translation);
+			let contacts_tx = self.contacts_tx.clone();
+			tokio::task::spawn(async move {
+				let contacts = query_contacts(&space, &colliders, point).await;
+				let _ = contacts_tx.send(contacts).await;
+			});
+		}
+	}
+	/// Fields that momentum bounces off when collision is on, usually not including the grabbable's own field
+	pub fn set_colliders(&mut self, colliders: impl IntoIterator<Item = Field>) {
+		self.colliders = colliders.into_iter().collect();
+	}
 	const ANGULAR_VELOCITY_STOP_THRESHOLD: f32 = 0.001;
 	fn apply_angular_momentum(&mut self, info: &FrameInfo, settings: MomentumSettings) {
 		let Some((axis, angle)) = &mut self.angular_velocity else {
@@ -203,6 +494,229 @@ impl Grabbable {
 			trace!(?axis, angle, "angular momentum");
 		}
 	}
+	/// Keeps momentum sliding along the constraints instead of fighting them
+	fn apply_constraints(&mut self) {
+		let constraints = self.settings.constraints;
+		self.pose = constraints.constrain_pose(self.pose);
+		if let Some(velocity) = &mut self.linear_velocity {
+			*velocity =
+				constraints.constrain_linear_velocity(self.pose.translation.into(), *velocity);
+		}
+		self.angular_velocity = self
+			.angular_velocity
+			.and_then(|(axis, angle)| constraints.constrain_angular_velocity(axis, angle));
+	}
+	/// Ease from where the previous input would put the grabbable to `pose` during a hand-off
+	fn blend_hand_off(&mut self, pose: Affine3A) -> Affine3A {
+		let Some(blend) = self.hand_off_blend else {
+			return pose;
+		};
+		let progress = blend.progress(self.settings.hand_off.blend_duration);
+		let previous_input = self
+			.input
+			.input_data()
+			.iter()
+			.find(|input| input.id == blend.from)
+			.cloned();
+		match previous_input {
+			Some(previous_input) if progress < 1.0 => blend_poses(
+				grab_pose(&previous_input) * blend.relative_transform,
+				pose,
+				progress,
+			),
+			_ => {
+				self.hand_off_blend.take();
+				pose
+			}
+		}
+	}
+	/// Follow `pose` right away, or through the springs in `frame` when following is enabled
+	fn move_to(&mut self, pose: Affine3A) {
+		let pose = self.settings.constraints.constrain_pose(pose);
+		if self.settings.follow.is_some() {
+			self.target_pose = pose;
+		} else {
+			self.pose = pose;
+			self.update_content_transform();
+		}
+	}
+	/// Snap fully into place once the grabbable comes to rest
+	fn settle(&mut self) {
+		self.show_snap_target(self.pose, self.pose);
+		if self.settings.snap.enabled() {
+			self.pose = self
+				.settings
+				.constraints
+				.constrain_pose(self.settings.snap.snap_pose(self.pose, true));
+			self.prev_pose = self.pose;
+			self.update_content_transform();
+		}
+		self.commit_history();
+	}
+
+	fn parent_target(&self) -> Option<ReparentTarget> {
+		self.reparentable.as_ref().and_then(|r| r.parent_target())
+	}
+	/// Record the current pose and parent
+	fn commit_history(&mut self) {
+		let entry = PoseEntry {
+			pose: self.pose,
+			parent: self.parent_target(),
+		};
+		self.history.commit(entry, self.settings.undo_history);
+	}
+	/// Go back to where the grabbable last came to rest before and what it was parented to, false if there's nothing to undo or it's being grabbed.
+	///
+	/// Zones aren't told when undo puts the grabbable back into them.
+	pub fn undo(&mut self) -> bool {
+		if self.grab_action.actor_acting() {
+			return false;
+		}
+		let Some(entry) = self.history.undo() else {
+			return false;
+		};
+		self.jump_to(entry);
+		true
+	}
+	pub fn redo(&mut self) -> bool {
+		if self.grab_action.actor_acting() {
+			return false;
+		}
+		let Some(entry) = self.history.redo() else {
+			return false;
+		};
+		self.jump_to(entry);
+		true
+	}
+	fn jump_to(&mut self, entry: PoseEntry) {
+		self.linear_velocity.take();
+		self.angular_velocity.take();
+		self.steer_target.take();
+		self.pose = entry.pose;
+		self.prev_pose = entry.pose;
+		self.update_content_transform();
+
+		if self.reparentable.is_none() {
+			self.make_reparentable();
+		}
+		if let Some(reparentable) = &self.reparentable
+			&& reparentable.parent_target() != entry.parent
+		{
+			// reparenting keeps it in place, so the order doesn't matter
+			let set_parent = reparentable.set_parent(entry.parent);
+			tokio::spawn(async move {
+				if let Err(e) = set_parent.await {
+					debug!(?e, "couldn't restore the parent from history");
+				}
+			});
+		}
+	}
+	fn handle_history_commands(&mut self) {
+		// history can be turned on after creation
+		if self.settings.undo_history > 0 && self._history_handles.is_none() {
+			self.serve_history();
+		}
+		while let Some(command) = self.history_rx.as_mut().and_then(|rx| rx.try_recv().ok()) {
+			match command {
+				HistoryCommand::Undo => self.undo(),
+				HistoryCommand::Redo => self.redo(),
+			};
+		}
+	}
+	fn update_parent_events(&mut self) {
+		let reparented = self.reparentable.as_ref().is_some_and(|r| r.reparented());
+		if reparented == self.was_reparented {
+			return;
+		}
+		self.was_reparented = reparented;
+		self.events.push(if reparented {
+			GrabbableEvent::Reparented {
+				to: self.reparentable.as_ref().and_then(|r| r.parented_to()),
+			}
+		} else {
+			GrabbableEvent::Unparented
+		});
+		// once it's back at rest in its new parent, not while a grab pulls it out
+		if !self.grab_action.actor_acting() {
+			self.commit_history();
+		}
+	}
+	/// Axes at the snap target with a line to the current pose, while debugging
+	fn show_snap_target(&self, pose: Affine3A, snapped: Affine3A) {
+		let Some(settings) = self.debug else {
+			return;
+		};
+		let mut lines = axes(0.01, settings.line_thickness);
+		if snapped != pose {
+			lines.extend(
+				axes(0.02, settings.line_thickness)
+					.into_iter()
+					.map(|l| l.transform(Mat4::from(snapped))),
+			);
+			lines.push(
+				line_from_points(vec![
+					Vec3::from(pose.translation),
+					Vec3::from(snapped.translation),
+				])
+				.color(settings.line_color)
+				.thickness(settings.line_thickness),
+			);
+		}
+		let _ = self.root_lines.set_lines(&lines);
+	}
+
+	/// Where momentum is taking the grabbable, in the content space
+	pub fn throw_prediction(&self) -> Option<ThrowPrediction> {
+		Some(ThrowPrediction {
+			origin: Vec3::from(self.pose.translation).into(),
+			velocity: self.linear_velocity?.into(),
+			drag: self.grab_input_settings.linear_momentum?.drag,
+		})
+	}
+	/// Zones that thrown grabbables get steered into when `throw_assist` is on
+	pub fn set_throw_targets(&mut self, zones: impl IntoIterator<Item = Arc<Zone>>) {
+		self.throw_targets = zones.into_iter().collect();
+	}
+	/// Change the momentum so the grabbable eases into `point` in the content space and stops there
+	pub fn steer_momentum_towards(&mut self, point: impl Into<Vector3<f32>>) {
+		let Some(drag) = self
+			.grab_input_settings
+			.linear_momentum
+			.map(|momentum| momentum.drag)
+			.filter(|drag| *drag > 0.0)
+		else {
+			return;
+		};
+		let target = Vec3::from(point.into());
+		let offset = target - Vec3::from(self.pose.translation);
+		self.linear_velocity.replace(offset * drag);
+		self.steer_target.replace(target);
+	}
+	fn predict_throw_target(&self) {
+		if !self.settings.throw_assist || self.throw_targets.is_empty() {
+			return;
+		}
+		let Some(prediction) = self.throw_prediction() else {
+			return;
+		};
+		let zones = self.throw_targets.clone();
+		let space = self.input.handler().clone();
+		let throw_target_tx = self.throw_target_tx.clone();
+		tokio::task::spawn(async move {
+			let Some((zone, entry)) = prediction.find_zone(&space, &zones, 16).await else {
+				return;
+			};
+			// the entry point is right on the zone's edge, aim for the middle so it ends up inside
+			let target = zone
+				.field()
+				.get_transform(&space)
+				.await
+				.ok()
+				.and_then(|transform| transform.translation)
+				.unwrap_or(entry);
+			let _ = throw_target_tx.send(target.into()).await;
+		});
+	}
 
 	pub fn linear_velocity(&self) -> Option<Vector3<f32>> {
 		self.linear_velocity.map(|v| v.into())
@@ -230,66 +744,195 @@ impl Grabbable {
 			&& self.angular_velocity.unwrap().1 < Self::ANGULAR_VELOCITY_STOP_THRESHOLD
 	}
 
+	/// Everything that happened since the last call, oldest first
+	pub fn drain_events(&mut self) -> impl Iterator<Item = GrabbableEvent> + '_ {
+		self.events.drain()
+	}
+
 	pub fn field(&self) -> &Field {
 		&self.field
 	}
 
+	pub fn input_queue(&self) -> &InputQueue {
+		&self.input
+	}
+	/// Mainly useful for recording or replaying input
+	pub fn input_queue_mut(&mut self) -> &mut InputQueue {
+		&mut self.input
+	}
 	pub fn grab_action(&self) -> &SingleAction {
 		&self.grab_action
 	}
+	/// Only updated when two handed grabbing is enabled
+	pub fn dual_action(&self) -> &DualAction {
+		&self.dual_action
+	}
 	pub fn content_parent(&self) -> SpatialRef {
 		self.content_parent.clone().as_spatial_ref()
 	}
 
-	pub fn pose(&self) -> (Vector3<f32>, Quaternion) {
-		let (_, rot, pos) = self.pose.to_scale_rotation_translation();
-		(pos.into(), rot.into())
+	/// Position, rotation and uniform scale
+	pub fn pose(&self) -> (Vector3<f32>, Quaternion, f32) {
+		let (scale, rot, pos) = self.pose.to_scale_rotation_translation();
+		(pos.into(), rot.into(), scale.x)
 	}
 
-	pub fn set_pose(&mut self, pos: impl Into<Vector3<f32>>, rot: impl Into<Quaternion>) {
+	pub fn set_pose(
+		&mut self,
+		pos: impl Into<Vector3<f32>>,
+		rot: impl Into<Quaternion>,
+		scale: f32,
+	) {
 		let pos = pos.into();
 		let rot = rot.into();
-		self.pose = Affine3A::from_rotation_translation(rot.into(), pos.into());
+		self.pose =
+			Affine3A::from_scale_rotation_translation(Vec3::splat(scale), rot.into(), pos.into());
+		self.update_content_transform();
+		self.commit_history();
+	}
+	fn update_content_transform(&mut self) {
+		// momentum and follow springs creep towards rest, that's not worth an event each frame
+		if !self.pose.abs_diff_eq(self.reported_pose, 0.0001) {
+			self.reported_pose = self.pose;
+			self.events.push(GrabbableEvent::Moved);
+		}
+		let (scale, rotation, translation) = self.pose.to_scale_rotation_translation();
 		self.content_parent
 			.set_relative_transform(
 				self.input.handler(),
-				Transform::from_translation_rotation(pos, rot),
+				Transform::from_translation_rotation_scale(translation, rotation, scale),
 			)
 			.unwrap();
 	}
 
+	/// Save this with the client's state to restore it with [`Grabbable::create_with_state`].
+	///
+	/// While reparented the pose is read back from the server, since whatever it's parented to may have moved it.
+	pub async fn state(&self) -> Result<GrabbableState, NodeError> {
+		let pose = if self.reparentable.as_ref().is_some_and(|r| r.reparented()) {
+			let transform = self
+				.content_parent
+				.get_transform(self.input.handler())
+				.await?;
+			Affine3A::from_scale_rotation_translation(
+				transform.scale.map_or(Vec3::ONE, Vec3::from),
+				transform.rotation.map_or(Quat::IDENTITY, Quat::from),
+				transform.translation.map_or(Vec3::ZERO, Vec3::from),
+			)
+		} else {
+			self.pose
+		};
+		Ok(GrabbableState::new(
+			pose,
+			self.linear_velocity,
+			self.angular_velocity,
+			self.parent_target(),
+		))
+	}
+	/// Momentum in the state carries on from where it was.
+	/// The parent is restored if its client still has it exported, otherwise it stays in the content space.
+	pub fn restore_state(&mut self, state: &GrabbableState) {
+		self.pose = state.pose();
+		self.prev_pose = self.pose;
+		self.linear_velocity = state.linear_velocity.map(Vec3::from);
+		self.angular_velocity = state
+			.angular_velocity
+			.map(|(axis, angle)| (Vec3::from(axis), angle));
+		self.history = History::new(PoseEntry {
+			pose: self.pose,
+			parent: state.parent.clone(),
+		});
+		self.update_content_transform();
+
+		if let Some(reparentable) = &self.reparentable
+			&& reparentable.parent_target() != state.parent
+		{
+			let set_parent = reparentable.set_parent(state.parent.clone());
+			tokio::spawn(async move {
+				if let Err(e) = set_parent.await {
+					debug!(?e, "couldn't restore the saved parent");
+				}
+			});
+		}
+	}
+
 	pub fn set_enabled(&self, enabled: bool) -> Result<(), NodeError> {
 		self.input.handler().set_enabled(enabled)
 	}
 }
 impl UIElement for Grabbable {
 	fn handle_events(&mut self) -> bool {
-		if !self.input.handle_events() {
+		let hold_elapsed = std::mem::take(&mut self.hold_elapsed);
+		if !self.input.handle_events() && !hold_elapsed {
 			return false;
 		}
-		self.grab_action.update(
-			true,
-			&self.input,
-			|input| {
-				let max_distance = self.settings.max_distance;
-				match &input.input {
-					InputDataType::Hand(h) => {
-						h.thumb.tip.distance < max_distance && h.index.tip.distance < max_distance
-					}
-					_ => input.distance < max_distance,
+		self.grab_condition
+			.update(&self.input, &self.settings.grab_condition);
+		let settings = self.settings;
+		let previous_actor = self.grab_action.actor().cloned();
+		let hover_condition = |input: &InputData| {
+			let max_distance = settings.input(&input.input).max_distance;
+			match &input.input {
+				InputDataType::Hand(h) => {
+					h.thumb.tip.distance < max_distance && h.index.tip.distance < max_distance
 				}
-			},
-			grab_pinch_interact,
-		);
+				_ => input.distance < max_distance,
+			}
+		};
+		if matches!(self.settings.hand_off.policy, HandOffPolicy::Ignore) {
+			// other inputs can still grab whatever's behind this
+			self.grab_action.update_exclusive(
+				&self.input,
+				hover_condition,
+				self.grab_condition.condition(),
+			);
+		} else {
+			self.grab_action.update(
+				matches!(self.settings.hand_off.policy, HandOffPolicy::Steal),
+				&self.input,
+				hover_condition,
+				self.grab_condition.condition(),
+			);
+		}
+		if matches!(self.settings.hand_off.policy, HandOffPolicy::TwoHanded(_)) {
+			// the grab action already captured both inputs
+			self.dual_action
+				.update_from(self.grab_action.interacting().current(), |input| {
+					grab_pose(input).translation.into()
+				});
+		}
+		if let Some(actor) = self.grab_action.actor() {
+			if self.grab_action.actor_started() {
+				self.events.push(GrabbableEvent::GrabStarted {
+					input: actor.clone(),
+				});
+			} else if self.grab_action.actor_changed()
+				&& let Some(previous_actor) = previous_actor
+			{
+				if self.settings.hand_off.blend_duration > 0.0 {
+					self.hand_off_blend = Some(HandOffBlend::new(
+						previous_actor.id,
+						self.relative_transform,
+					));
+				}
+				self.events.push(GrabbableEvent::HandOff {
+					from: previous_actor,
+					to: actor.clone(),
+				});
+			}
+		}
 		let start_grabbing = self.waiting_for_transform
 			|| (self.transform_changed.is_none() && self.grab_action().actor_started());
 		if let Some(recv) = self.transform_changed.as_ref()
 			&& let Some(pose) = recv.try_changed()
 		{
-			self.pose = Affine3A::from_rotation_translation(
+			self.pose = Affine3A::from_scale_rotation_translation(
+				pose.scale.map_or(Vec3::ONE, Vec3::from),
 				pose.rotation.map_or(Quat::IDENTITY, Quat::from),
 				pose.translation.map_or(Vec3::ZERO, Vec3::from),
 			);
+			// coming back from a zone counts as a move
+			self.commit_history();
 			self.relative_transform = Affine3A::IDENTITY;
 			self.waiting_for_transform = false;
 			self.transform_changed
@@ -298,6 +941,7 @@ impl UIElement for Grabbable {
 		if self.grab_action.actor_started() && self.transform_changed.is_some() {
 			self.reparentable.take();
 			self.waiting_for_transform = true;
+			self.update_parent_events();
 		}
 
 		if self.waiting_for_transform {
@@ -305,55 +949,65 @@ impl UIElement for Grabbable {
 			return true;
 		}
 
-		if start_grabbing || self.grab_action.actor_changed() {
-			// Calculate and store the relative transform matrix
-			let actor = self.grab_action.actor().unwrap();
-			let grab_position = match &actor.input {
-				InputDataType::Pointer(p) => p.origin.into(),
-				InputDataType::Hand(h) => h.palm.position.into(),
-				InputDataType::Tip(t) => t.origin.into(),
-			};
-			let grab_rotation = match &actor.input {
-				InputDataType::Pointer(p) => p.orientation.into(),
-				InputDataType::Hand(h) => h.palm.rotation.into(),
-				InputDataType::Tip(t) => t.orientation.into(),
-			};
-			let grab_pose_matrix =
-				Affine3A::from_rotation_translation(grab_rotation, grab_position);
+		let mut dual_acting = false;
+		if let HandOffPolicy::TwoHanded(two_handed) = self.settings.hand_off.policy
+			&& self.grab_action.actor_acting()
+		{
+			if self.dual_action.started() {
+				self.dual_start_pose = self.pose;
+			}
+			if self.dual_action.acting() {
+				dual_acting = true;
+				let total = self.dual_action.total();
+				let start_scale = self.dual_start_pose.to_scale_rotation_translation().0.x;
+				let scale =
+					(start_scale * total.scale).clamp(two_handed.min_scale, two_handed.max_scale);
+				self.move_to(
+					DualTransform {
+						scale: scale / start_scale,
+						..total
+					}
+					.matrix() * self.dual_start_pose,
+				);
+			}
+		}
 
-			self.relative_transform = grab_pose_matrix.inverse() * self.pose;
+		// the second hand letting go means the first one has to pick up from the new pose
+		if (start_grabbing || self.grab_action.actor_changed() || self.dual_action.stopped())
+			&& let Some(actor) = self.grab_action.actor()
+		{
+			// Calculate and store the relative transform matrix
+			self.relative_transform = grab_pose(actor).inverse() * self.pose;
 			self.prev_pose = self.pose;
+			self.target_pose = self.pose;
 		}
 
-		if let Some(actor) = self.grab_action.actor().cloned() {
-			if matches!(&actor.input, InputDataType::Pointer(_)) {
+		if !dual_acting && let Some(actor) = self.grab_action.actor().cloned() {
+			let input_settings = *self.settings.input(&actor.input);
+			self.grab_input_settings = input_settings;
+			if input_settings.scroll_gain != 0.0 {
+				let gain = input_settings.scroll_gain;
 				let scroll_amount = actor.datamap.with_data(|datamap| {
-					(datamap.idx("scroll_continuous").as_vector().idx(1).as_f32() * 0.01) + // Use the Y-axis for forward/backward scrolling
-					(datamap.idx("scroll_discrete").as_vector().idx(1).as_f32() * 0.01) // Use the Y-axis for forward/backward scrolling
+					(datamap.idx("scroll_continuous").as_vector().idx(1).as_f32() * gain) + // Use the Y-axis for forward/backward scrolling
+					(datamap.idx("scroll_discrete").as_vector().idx(1).as_f32() * gain) // Use the Y-axis for forward/backward scrolling
 				});
 				let offset = Affine3A::from_translation(vec3(0.0, 0.0, scroll_amount));
 				self.relative_transform = offset * self.relative_transform;
 			}
 
-			let grab_position = match &actor.input {
-				InputDataType::Pointer(p) => p.origin.into(),
-				InputDataType::Hand(h) => h.palm.position.into(),
-				InputDataType::Tip(t) => t.origin.into(),
-			};
-			let grab_rotation = match &actor.input {
-				InputDataType::Pointer(p) => p.orientation.into(),
-				InputDataType::Hand(h) => h.palm.rotation.into(),
-				InputDataType::Tip(t) => t.orientation.into(),
-			};
-			let current_grab_pose =
-				Affine3A::from_rotation_translation(grab_rotation, grab_position);
+			let current_grab_pose = grab_pose(&actor);
 
-			self.pose = match (&actor.input, self.settings.pointer_mode) {
+			let pose = match (&actor.input, self.settings.pointer_mode) {
 				(InputDataType::Pointer(p), PointerMode::Align) => {
 					let parent_pose = current_grab_pose * self.relative_transform;
-					let (_, _, parent_translation) = parent_pose.to_scale_rotation_translation();
+					let (parent_scale, _, parent_translation) =
+						parent_pose.to_scale_rotation_translation();
 					let swing_rotation = swing_direction(p.direction().into());
-					Affine3A::from_rotation_translation(swing_rotation, parent_translation)
+					Affine3A::from_scale_rotation_translation(
+						parent_scale,
+						swing_rotation,
+						parent_translation,
+					)
 				}
 				(InputDataType::Pointer(_), PointerMode::Move) => {
 					let parent_pose = current_grab_pose * self.relative_transform;
@@ -363,14 +1017,14 @@ impl UIElement for Grabbable {
 				}
 				(_, _) => current_grab_pose * self.relative_transform,
 			};
-
-			let (_, new_rotation, new_position) = self.pose.to_scale_rotation_translation();
-			self.content_parent
-				.set_relative_transform(
-					self.input.handler(),
-					Transform::from_translation_rotation(new_position, new_rotation),
-				)
-				.unwrap();
+			let pose = self.blend_hand_off(pose);
+			if self.settings.snap.enabled() {
+				let snapped = self.settings.snap.snap_pose(pose, false);
+				self.show_snap_target(pose, snapped);
+				self.move_to(self.settings.snap.blend(pose, snapped));
+			} else {
+				self.move_to(pose);
+			}
 		}
 
 		if start_grabbing {
@@ -378,35 +1032,40 @@ impl UIElement for Grabbable {
 				id = self.grab_action.actor().as_ref().unwrap().id,
 				"Started grabbing"
 			);
-			'magnet: {
-				if self.settings.magnet {
-					// if we have magnet strength, store the closest point so we can lerp that to the grab point
-					let grab_data = self.grab_action.actor().unwrap().clone();
-					// pointers are just too unstable to magnet
-					if let InputDataType::Pointer(_) = &grab_data.input {
-						break 'magnet;
-					}
-					let field = self.field.clone();
-					let input = self.input.handler().clone();
-					let closest_point_tx = self.closest_point_tx.clone();
-					tokio::task::spawn(async move {
-						let result = field.closest_point(&input, [0.0; 3]).await.unwrap();
-						// if let Ok(result) = result {
-						let _ = closest_point_tx.send(result.into()).await;
-						// }
-					});
-				}
+			// don't steer towards where it was thrown last time
+			let _ = self.throw_target_rx.try_recv();
+			self.steer_target.take();
+			if self.grab_input_settings.magnet {
+				// if we have magnet strength, store the closest point so we can lerp that to the grab point
+				let field = self.field.clone();
+				let input = self.input.handler().clone();
+				let closest_point_tx = self.closest_point_tx.clone();
+				tokio::task::spawn(async move {
+					let result = field.closest_point(&input, [0.0; 3]).await.unwrap();
+					// if let Ok(result) = result {
+					let _ = closest_point_tx.send(result.into()).await;
+					// }
+				});
 			}
 		}
 
 		if self.grab_action.actor_stopped() {
 			debug!("Stopped grabbing");
+			self.events.push(GrabbableEvent::Released {
+				velocity: self.linear_velocity(),
+			});
+			self.predict_throw_target();
 
 			if self.linear_velocity().is_none() {
 				self.make_reparentable();
 			}
 
 			self.relative_transform = Affine3A::IDENTITY;
+			self.spring.reset();
+			self.hand_off_blend.take();
+			if self.linear_velocity.is_none() && self.angular_velocity.is_none() {
+				self.settle();
+			}
 
 			let _ = self.closest_point_rx.try_recv();
 		}
@@ -415,16 +1074,30 @@ impl UIElement for Grabbable {
 }
 impl FrameSensitive for Grabbable {
 	fn frame(&mut self, info: &FrameInfo) {
+		self.hold_elapsed |= self
+			.grab_condition
+			.update_hold(&self.settings.grab_condition);
+		self.handle_history_commands();
+		self.update_parent_events();
+		if let Some(follow) = self.settings.follow
+			&& self.grab_action.actor_acting()
+			&& !self.waiting_for_transform
+		{
+			self.pose = self
+				.spring
+				.step(self.pose, self.target_pose, &follow, info.delta);
+			self.update_content_transform();
+		}
 		if self.grab_action.actor_acting() {
 			let delta = info.delta;
 			let velocity = self.pose * self.prev_pose.inverse();
 			let (_, angular_velocity, linear_velocity) = velocity.to_scale_rotation_translation();
-			if let Some(momentum_settings) = &self.settings.linear_momentum {
+			if let Some(momentum_settings) = &self.grab_input_settings.linear_momentum {
 				let above_threshold =
 					linear_velocity.length_squared() > momentum_settings.threshold.powf(2.0);
 				self.linear_velocity = above_threshold.then(|| linear_velocity / delta);
 			}
-			if let Some(momentum_settings) = &self.settings.angular_momentum {
+			if let Some(momentum_settings) = &self.grab_input_settings.angular_momentum {
 				let (axis, angle) = angular_velocity.to_axis_angle();
 				let above_threshold = angle > momentum_settings.threshold;
 				self.angular_velocity = above_threshold.then(|| (axis, angle / delta));
@@ -434,28 +1107,33 @@ impl FrameSensitive for Grabbable {
 		if (!self.grab_action.actor_acting())
 			&& !self.reparentable.as_ref().is_some_and(|v| v.reparented())
 		{
-			if let Some(settings) = self.settings.linear_momentum {
+			if let Ok(target) = self.throw_target_rx.try_recv()
+				&& self.linear_velocity.is_some()
+			{
+				self.steer_momentum_towards(target);
+			}
+			let was_moving = self.linear_velocity.is_some() || self.angular_velocity.is_some();
+			if let Some(settings) = self.grab_input_settings.linear_momentum {
 				self.apply_linear_momentum(info, settings);
 			}
-			if let Some(settings) = self.settings.angular_momentum {
+			if let Some(settings) = self.grab_input_settings.angular_momentum {
 				self.apply_angular_momentum(info, settings);
 			}
 
 			if self.linear_velocity.is_some() || self.angular_velocity.is_some() {
+				self.apply_constraints();
 				self.prev_pose = self.pose;
-				let (_, rotation, translation) = self.pose.to_scale_rotation_translation();
-				self.content_parent
-					.set_relative_transform(
-						self.input.handler(),
-						Transform::from_translation_rotation(translation, rotation),
-					)
-					.unwrap();
+				self.update_content_transform();
+			} else if was_moving {
+				self.events.push(GrabbableEvent::MomentumStopped);
+				self.settle();
 			}
 		}
 	}
 }
 impl VisualDebug for Grabbable {
-	fn set_debug(&mut self, settings: Option<crate::DebugSettings>) {
+	fn set_debug(&mut self, settings: Option<DebugSettings>) {
+		self.debug = settings;
 		if let Some(settings) = settings {
 			let _ = self
 				.root_lines
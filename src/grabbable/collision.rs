@@ -0,0 +1,103 @@
+use glam::Vec3;
+use stardust_xr_fusion::{
+	fields::{Field, FieldRefAspect},
+	spatial::SpatialRefAspect,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CollisionSettings {
+	/// How much of the speed into a surface is kept when bouncing off it, 0 stops dead and 1 bounces perfectly
+	pub restitution: f32,
+	/// How much of the speed along a surface is lost when hitting it, from 0 to 1
+	pub friction: f32,
+	/// The grabbable collides as a sphere of this radius around its origin
+	pub radius: f32,
+}
+impl Default for CollisionSettings {
+	fn default() -> Self {
+		Self {
+			restitution: 0.4,
+			friction: 0.2,
+			radius: 0.05,
+		}
+	}
+}
+
+/// Nearest point of a collider, `normal` points out of it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Contact {
+	pub(crate) distance: f32,
+	pub(crate) normal: Vec3,
+}
+
+pub(crate) async fn query_contacts(
+	space: &impl SpatialRefAspect,
+	colliders: &[Field],
+	point: Vec3,
+) -> Vec<Contact> {
+	let mut contacts = Vec::new();
+	for collider in colliders {
+		let (Ok(distance), Ok(closest_point)) = futures_util::join!(
+			collider.distance(space, point),
+			collider.closest_point(space, point)
+		) else {
+			continue;
+		};
+		let offset = point - Vec3::from(closest_point);
+		// inside the field the closest point is behind us
+		let normal = offset.normalize_or_zero() * distance.signum();
+		if normal != Vec3::ZERO {
+			contacts.push(Contact { distance, normal });
+		}
+	}
+	contacts
+}
+
+impl CollisionSettings {
+	/// New position and velocity after bouncing off `contact`, None if it's not touching or moving away.
+	///
+	/// Contacts are a frame or two old by the time they arrive, so it looks ahead by how far it could've moved since.
+	pub(crate) fn respond(
+		&self,
+		contact: &Contact,
+		position: Vec3,
+		velocity: Vec3,
+		delta: f32,
+	) -> Option<(Vec3, Vec3)> {
+		let approach_speed = -velocity.dot(contact.normal);
+		if approach_speed <= 0.0 || contact.distance > self.radius + approach_speed * delta * 2.0 {
+			return None;
+		}
+		let normal_velocity = contact.normal * velocity.dot(contact.normal);
+		let tangent_velocity = velocity - normal_velocity;
+		let velocity = tangent_velocity * (1.0 - self.friction.clamp(0.0, 1.0))
+			- normal_velocity * self.restitution.max(0.0);
+		let position = position + contact.normal * (self.radius - contact.distance).max(0.0);
+		Some((position, velocity))
+	}
+}
+
+#[test]
+fn collision_bounce() {
+	let settings = CollisionSettings {
+		restitution: 0.5,
+		friction: 0.5,
+		radius: 0.1,
+	};
+	let floor = Contact {
+		distance: 0.05,
+		normal: Vec3::Y,
+	};
+	let (position, velocity) = settings
+		.respond(&floor, Vec3::ZERO, Vec3::new(1.0, -2.0, 0.0), 1.0 / 60.0)
+		.unwrap();
+	assert!(position.abs_diff_eq(Vec3::new(0.0, 0.05, 0.0), 0.0001));
+	assert!(velocity.abs_diff_eq(Vec3::new(0.5, 1.0, 0.0), 0.0001));
+
+	// already bouncing away
+	assert!(
+		settings
+			.respond(&floor, position, velocity, 1.0 / 60.0)
+			.is_none()
+	);
+}
@@ -0,0 +1,164 @@
+use glam::{Affine3A, EulerRot, Quat, Vec3};
+use stardust_xr_fusion::values::Vector3;
+
+/// Limits on where the grabbable can move, in the content space.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TranslationConstraint {
+	#[default]
+	Free,
+	/// Slide along a line, e.g. a slider handle
+	Axis {
+		origin: Vector3<f32>,
+		direction: Vector3<f32>,
+	},
+	/// Stay on a plane, e.g. something on a table
+	Plane {
+		origin: Vector3<f32>,
+		normal: Vector3<f32>,
+	},
+}
+
+/// Axis aligned box in the content space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+	pub min: Vector3<f32>,
+	pub max: Vector3<f32>,
+}
+
+/// Which axes of the content space the grabbable can't rotate around.
+///
+/// Locked angles are kept at the content space's, so e.g. locking X and Z keeps it upright.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RotationLock {
+	pub x: bool,
+	pub y: bool,
+	pub z: bool,
+}
+impl RotationLock {
+	pub const ALL: Self = RotationLock {
+		x: true,
+		y: true,
+		z: true,
+	};
+}
+
+/// Applied while grabbed and during momentum, so constrained objects coast along their constraints too.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GrabbableConstraints {
+	pub translation: TranslationConstraint,
+	pub bounds: Option<Bounds>,
+	pub rotation_lock: RotationLock,
+}
+impl GrabbableConstraints {
+	pub fn constrain_pose(&self, pose: Affine3A) -> Affine3A {
+		let (scale, rotation, translation) = pose.to_scale_rotation_translation();
+		Affine3A::from_scale_rotation_translation(
+			scale,
+			self.constrain_rotation(rotation),
+			self.constrain_translation(translation),
+		)
+	}
+	pub fn constrain_translation(&self, translation: Vec3) -> Vec3 {
+		let translation = match self.translation {
+			TranslationConstraint::Free => translation,
+			TranslationConstraint::Axis { origin, direction } => {
+				let origin = Vec3::from(origin);
+				let direction = Vec3::from(direction).normalize_or_zero();
+				origin + direction * (translation - origin).dot(direction)
+			}
+			TranslationConstraint::Plane { origin, normal } => {
+				let normal = Vec3::from(normal).normalize_or_zero();
+				translation - normal * (translation - Vec3::from(origin)).dot(normal)
+			}
+		};
+		match self.bounds {
+			Some(bounds) => translation.clamp(bounds.min.into(), bounds.max.into()),
+			None => translation,
+		}
+	}
+	pub fn constrain_rotation(&self, rotation: Quat) -> Quat {
+		let lock = self.rotation_lock;
+		if !(lock.x || lock.y || lock.z) {
+			return rotation;
+		}
+		let (y, x, z) = rotation.to_euler(EulerRot::YXZ);
+		Quat::from_euler(
+			EulerRot::YXZ,
+			if lock.y { 0.0 } else { y },
+			if lock.x { 0.0 } else { x },
+			if lock.z { 0.0 } else { z },
+		)
+	}
+
+	/// Keep momentum going along the constraints, and stop it at the bounds.
+	pub fn constrain_linear_velocity(&self, translation: Vec3, velocity: Vec3) -> Vec3 {
+		let mut velocity = match self.translation {
+			TranslationConstraint::Free => velocity,
+			TranslationConstraint::Axis { direction, .. } => {
+				let direction = Vec3::from(direction).normalize_or_zero();
+				direction * velocity.dot(direction)
+			}
+			TranslationConstraint::Plane { normal, .. } => {
+				let normal = Vec3::from(normal).normalize_or_zero();
+				velocity - normal * velocity.dot(normal)
+			}
+		};
+		if let Some(bounds) = self.bounds {
+			let min = Vec3::from(bounds.min);
+			let max = Vec3::from(bounds.max);
+			for axis in 0..3 {
+				if (translation[axis] <= min[axis] && velocity[axis] < 0.0)
+					|| (translation[axis] >= max[axis] && velocity[axis] > 0.0)
+				{
+					velocity[axis] = 0.0;
+				}
+			}
+		}
+		velocity
+	}
+	/// Remove the locked axes from the rotation axis, None if nothing is left to rotate around.
+	pub fn constrain_angular_velocity(&self, axis: Vec3, angle: f32) -> Option<(Vec3, f32)> {
+		let lock = self.rotation_lock;
+		let mut angular_velocity = axis * angle;
+		for (locked, axis) in [(lock.x, 0), (lock.y, 1), (lock.z, 2)] {
+			if locked {
+				angular_velocity[axis] = 0.0;
+			}
+		}
+		let angle = angular_velocity.length();
+		(angle > f32::EPSILON).then(|| (angular_velocity / angle, angle))
+	}
+}
+
+#[test]
+fn axis_constraint_with_bounds() {
+	let constraints = GrabbableConstraints {
+		translation: TranslationConstraint::Axis {
+			origin: [0.0, 1.0, 0.0].into(),
+			direction: [1.0, 0.0, 0.0].into(),
+		},
+		bounds: Some(Bounds {
+			min: [-0.5, -10.0, -10.0].into(),
+			max: [0.5, 10.0, 10.0].into(),
+		}),
+		rotation_lock: RotationLock::ALL,
+	};
+
+	let pose =
+		Affine3A::from_rotation_translation(Quat::from_rotation_y(1.0), Vec3::new(0.25, 0.0, 3.0));
+	let (_, rotation, translation) = constraints
+		.constrain_pose(pose)
+		.to_scale_rotation_translation();
+	assert!(translation.abs_diff_eq(Vec3::new(0.25, 1.0, 0.0), 0.0001));
+	assert!(rotation.abs_diff_eq(Quat::IDENTITY, 0.0001));
+
+	let translation = constraints.constrain_translation(Vec3::new(2.0, 0.0, 0.0));
+	assert!(translation.abs_diff_eq(Vec3::new(0.5, 1.0, 0.0), 0.0001));
+	let velocity = constraints.constrain_linear_velocity(translation, Vec3::new(1.0, 1.0, 0.0));
+	assert_eq!(velocity, Vec3::ZERO);
+	assert!(
+		constraints
+			.constrain_angular_velocity(Vec3::Y, 1.0)
+			.is_none()
+	);
+}
@@ -0,0 +1,52 @@
+use stardust_xr_fusion::{input::InputData, objects::zbus::names::UniqueName, values::Vector3};
+use std::{collections::VecDeque, sync::Arc};
+
+/// Something that happened to a [`Grabbable`](super::Grabbable), in the order it happened.
+#[derive(Debug, Clone)]
+pub enum GrabbableEvent {
+	GrabStarted {
+		input: Arc<InputData>,
+	},
+	/// Another input took over the grab
+	HandOff {
+		from: Arc<InputData>,
+		to: Arc<InputData>,
+	},
+	/// The pose changed, from grabbing, momentum or snapping
+	Moved,
+	/// `velocity` is None if it was let go without momentum
+	Released {
+		velocity: Option<Vector3<f32>>,
+	},
+	/// Momentum after being released ran out
+	MomentumStopped,
+	/// Parented to a zone, `to` is the bus name of the client that did it if known
+	Reparented {
+		to: Option<UniqueName<'static>>,
+	},
+	/// Back in its original parent after being reparented
+	Unparented,
+}
+
+/// Oldest events get dropped after this many so the queue can't grow forever if nobody drains it.
+const MAX_QUEUED_EVENTS: usize = 256;
+
+#[derive(Debug, Default)]
+pub(crate) struct GrabbableEvents(VecDeque<GrabbableEvent>);
+impl GrabbableEvents {
+	pub(crate) fn push(&mut self, event: GrabbableEvent) {
+		// don't flood the queue with moves nobody has read yet
+		if matches!(event, GrabbableEvent::Moved)
+			&& matches!(self.0.back(), Some(GrabbableEvent::Moved))
+		{
+			return;
+		}
+		if self.0.len() >= MAX_QUEUED_EVENTS {
+			self.0.pop_front();
+		}
+		self.0.push_back(event);
+	}
+	pub(crate) fn drain(&mut self) -> impl Iterator<Item = GrabbableEvent> + '_ {
+		self.0.drain(..)
+	}
+}
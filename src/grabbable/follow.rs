@@ -0,0 +1,95 @@
+use glam::{Affine3A, Quat, Vec3};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpringSettings {
+	pub stiffness: f32,
+	pub damping: f32,
+}
+impl SpringSettings {
+	/// Gets to the target as fast as possible without overshooting
+	pub fn critically_damped(stiffness: f32) -> Self {
+		SpringSettings {
+			stiffness,
+			damping: 2.0 * stiffness.sqrt(),
+		}
+	}
+}
+
+/// Springs pulling the grabbable towards where the grabbing input wants it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FollowSettings {
+	pub translation: SpringSettings,
+	pub rotation: SpringSettings,
+}
+impl Default for FollowSettings {
+	fn default() -> Self {
+		Self {
+			translation: SpringSettings::critically_damped(400.0),
+			rotation: SpringSettings::critically_damped(400.0),
+		}
+	}
+}
+
+/// Longest step the springs take at once so stiff springs don't blow up on long frames
+const MAX_STEP: f32 = 1.0 / 240.0;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct SpringFollow {
+	linear_velocity: Vec3,
+	/// Scaled axis
+	angular_velocity: Vec3,
+}
+impl SpringFollow {
+	pub(crate) fn step(
+		&mut self,
+		pose: Affine3A,
+		target: Affine3A,
+		settings: &FollowSettings,
+		delta: f32,
+	) -> Affine3A {
+		let (_, mut rotation, mut translation) = pose.to_scale_rotation_translation();
+		let (target_scale, target_rotation, target_translation) =
+			target.to_scale_rotation_translation();
+
+		let steps = (delta / MAX_STEP).ceil().max(1.0);
+		let dt = delta / steps;
+		for _ in 0..steps as usize {
+			let spring = settings.translation;
+			let acceleration = (target_translation - translation) * spring.stiffness
+				- self.linear_velocity * spring.damping;
+			self.linear_velocity += acceleration * dt;
+			translation += self.linear_velocity * dt;
+
+			let spring = settings.rotation;
+			let mut error = target_rotation * rotation.inverse();
+			// take the short way around
+			if error.w < 0.0 {
+				error = -error;
+			}
+			let acceleration =
+				error.to_scaled_axis() * spring.stiffness - self.angular_velocity * spring.damping;
+			self.angular_velocity += acceleration * dt;
+			rotation = (Quat::from_scaled_axis(self.angular_velocity * dt) * rotation).normalize();
+		}
+		Affine3A::from_scale_rotation_translation(target_scale, rotation, translation)
+	}
+	pub(crate) fn reset(&mut self) {
+		*self = Self::default();
+	}
+}
+
+#[test]
+fn spring_follow_settles() {
+	let mut follow = SpringFollow::default();
+	let settings = FollowSettings::default();
+	let target = Affine3A::from_rotation_translation(Quat::from_rotation_y(1.0), Vec3::X);
+	let mut pose = Affine3A::IDENTITY;
+
+	pose = follow.step(pose, target, &settings, 1.0 / 60.0);
+	// trails behind instead of jumping
+	assert!(pose.translation.x > 0.0 && pose.translation.x < 0.5);
+	for _ in 0..120 {
+		pose = follow.step(pose, target, &settings, 1.0 / 60.0);
+	}
+	assert!(pose.abs_diff_eq(target, 0.001));
+}
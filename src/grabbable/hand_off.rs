@@ -0,0 +1,67 @@
+use super::TwoHandedSettings;
+use glam::Affine3A;
+use std::time::Instant;
+
+/// What happens when another input grabs the grabbable while it's already grabbed.
+#[derive(Debug, Clone, Copy)]
+pub enum HandOffPolicy {
+	/// The new input takes over the grab
+	Steal,
+	/// Only the first input can move it until it lets go
+	Ignore,
+	/// The second input scales and rotates it instead.
+	/// The grab ends once the first input lets go, even if the second is still grabbing.
+	TwoHanded(TwoHandedSettings),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct HandOffSettings {
+	pub policy: HandOffPolicy,
+	/// Seconds to blend from following the old input to the new one when stealing, 0 switches instantly
+	pub blend_duration: f32,
+}
+impl Default for HandOffSettings {
+	fn default() -> Self {
+		Self {
+			policy: HandOffPolicy::Steal,
+			blend_duration: 0.15,
+		}
+	}
+}
+
+/// Blends between the pose from the previous grabbing input and the new one.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HandOffBlend {
+	pub(crate) from: u64,
+	/// Relative to the previous input's grab pose
+	pub(crate) relative_transform: Affine3A,
+	started: Instant,
+}
+impl HandOffBlend {
+	pub(crate) fn new(from: u64, relative_transform: Affine3A) -> Self {
+		HandOffBlend {
+			from,
+			relative_transform,
+			started: Instant::now(),
+		}
+	}
+	/// Progress from 0 to 1, eased so the handoff doesn't start or end with a jolt
+	pub(crate) fn progress(&self, duration: f32) -> f32 {
+		if duration <= 0.0 {
+			return 1.0;
+		}
+		let t = (self.started.elapsed().as_secs_f32() / duration).clamp(0.0, 1.0);
+		t * t * (3.0 - 2.0 * t)
+	}
+}
+
+/// Interpolate the translation, rotation and scale of 2 poses
+pub(crate) fn blend_poses(from: Affine3A, to: Affine3A, amount: f32) -> Affine3A {
+	let (from_scale, from_rotation, from_translation) = from.to_scale_rotation_translation();
+	let (to_scale, to_rotation, to_translation) = to.to_scale_rotation_translation();
+	Affine3A::from_scale_rotation_translation(
+		from_scale.lerp(to_scale, amount),
+		from_rotation.slerp(to_rotation, amount),
+		from_translation.lerp(to_translation, amount),
+	)
+}
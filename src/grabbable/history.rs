@@ -0,0 +1,74 @@
+use crate::reparentable::ReparentTarget;
+use glam::Affine3A;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryCommand {
+	Undo,
+	Redo,
+}
+
+/// Where the grabbable came to rest and what it was parented to at the time.
+#[derive(Debug, Clone)]
+pub(crate) struct PoseEntry {
+	pub(crate) pose: Affine3A,
+	pub(crate) parent: Option<ReparentTarget>,
+}
+/// Tiny pose differences from float noise don't count as a move
+impl PartialEq for PoseEntry {
+	fn eq(&self, other: &Self) -> bool {
+		self.parent == other.parent && self.pose.abs_diff_eq(other.pose, 0.0001)
+	}
+}
+
+pub(crate) struct PoseHistoryInner(pub(crate) mpsc::Sender<HistoryCommand>);
+/// Served on the same path as the grabbable's [`Reparentable`](crate::reparentable::Reparentable) so other clients can undo moves.
+#[zbus::interface(
+	name = "org.stardustxr.PoseHistory",
+	proxy(async_name = "PoseHistoryProxy")
+)]
+impl PoseHistoryInner {
+	async fn undo(&self) {
+		let _ = self.0.send(HistoryCommand::Undo).await;
+	}
+	async fn redo(&self) {
+		let _ = self.0.send(HistoryCommand::Redo).await;
+	}
+}
+
+#[test]
+fn pose_history_undo_redo() {
+	use crate::persistence::History;
+	use glam::Vec3;
+
+	let at = |x| PoseEntry {
+		pose: Affine3A::from_translation(Vec3::new(x, 0.0, 0.0)),
+		parent: None,
+	};
+	let mut history = History::new(at(0.0));
+	history.commit(at(1.0), 2);
+	history.commit(at(2.0), 2);
+	history.commit(at(3.0), 2);
+	// the oldest pose got dropped past the limit
+	assert_eq!(history.undo(), Some(at(2.0)));
+	assert_eq!(history.undo(), Some(at(1.0)));
+	assert_eq!(history.undo(), None);
+	assert_eq!(history.redo(), Some(at(2.0)));
+
+	// moving after undoing throws away the redo
+	history.commit(at(5.0), 2);
+	assert_eq!(history.redo(), None);
+	assert_eq!(history.undo(), Some(at(2.0)));
+
+	// a parent change alone is still a change
+	let parented = PoseEntry {
+		parent: Some(ReparentTarget {
+			client: ":1.5".to_string(),
+			spatial: 3,
+		}),
+		..at(2.0)
+	};
+	history.commit(parented.clone(), 2);
+	assert_eq!(history.undo(), Some(at(2.0)));
+	assert_eq!(history.redo(), Some(parented));
+}
@@ -0,0 +1,85 @@
+use glam::{Affine3A, EulerRot, Quat, Vec3};
+
+/// Snapping in the content space, whose +Y is treated as up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapSettings {
+	/// Grid cell size in meters, None means positions don't snap
+	pub grid: Option<f32>,
+	/// Yaw step in radians, None means rotation doesn't snap
+	pub angle: Option<f32>,
+	/// Rotate the grabbable upright when it's released
+	pub gravity_up: bool,
+	/// How far towards the snapped pose the grabbable gets pulled while grabbed, from 0 to 1.
+	/// Once released it always settles fully onto it.
+	pub strength: f32,
+}
+impl Default for SnapSettings {
+	fn default() -> Self {
+		Self {
+			grid: None,
+			angle: None,
+			gravity_up: false,
+			strength: 1.0,
+		}
+	}
+}
+impl SnapSettings {
+	/// Snap to the grid and angle steps, and upright if `upright` is true.
+	pub fn snap_pose(&self, pose: Affine3A, upright: bool) -> Affine3A {
+		let (scale, mut rotation, mut translation) = pose.to_scale_rotation_translation();
+		if let Some(grid) = self.grid.filter(|grid| *grid > 0.0) {
+			translation = (translation / grid).round() * grid;
+		}
+		if upright && self.gravity_up {
+			rotation =
+				(Quat::from_rotation_arc(rotation * Vec3::Y, Vec3::Y) * rotation).normalize();
+		}
+		if let Some(step) = self.angle.filter(|step| *step > 0.0) {
+			let (yaw, pitch, roll) = rotation.to_euler(EulerRot::YXZ);
+			rotation = Quat::from_euler(EulerRot::YXZ, (yaw / step).round() * step, pitch, roll);
+		}
+		Affine3A::from_scale_rotation_translation(scale, rotation, translation)
+	}
+	/// Pull `pose` towards `snapped` by the snap strength.
+	pub fn blend(&self, pose: Affine3A, snapped: Affine3A) -> Affine3A {
+		let strength = self.strength.clamp(0.0, 1.0);
+		let (scale, rotation, translation) = pose.to_scale_rotation_translation();
+		let (_, snapped_rotation, snapped_translation) = snapped.to_scale_rotation_translation();
+		Affine3A::from_scale_rotation_translation(
+			scale,
+			rotation.slerp(snapped_rotation, strength),
+			translation.lerp(snapped_translation, strength),
+		)
+	}
+	pub fn enabled(&self) -> bool {
+		self.grid.is_some() || self.angle.is_some() || self.gravity_up
+	}
+}
+
+#[test]
+fn snap_grid_and_angle() {
+	use std::f32::consts::PI;
+
+	let settings = SnapSettings {
+		grid: Some(0.1),
+		angle: Some(PI / 12.0),
+		gravity_up: true,
+		strength: 0.5,
+	};
+	let pose = Affine3A::from_rotation_translation(
+		Quat::from_rotation_y(PI / 12.0 + 0.05) * Quat::from_rotation_x(0.3),
+		Vec3::new(0.12, 0.0, -0.26),
+	);
+
+	let (_, rotation, translation) = settings
+		.snap_pose(pose, true)
+		.to_scale_rotation_translation();
+	assert!(translation.abs_diff_eq(Vec3::new(0.1, 0.0, -0.3), 0.0001));
+	assert!((rotation * Vec3::Y).abs_diff_eq(Vec3::Y, 0.0001));
+	assert!(rotation.abs_diff_eq(Quat::from_rotation_y(PI / 12.0), 0.0001));
+
+	let (_, _, translation) = settings
+		.blend(pose, settings.snap_pose(pose, false))
+		.to_scale_rotation_translation();
+	assert!(translation.abs_diff_eq(Vec3::new(0.11, 0.0, -0.28), 0.0001));
+}
@@ -0,0 +1,68 @@
+use crate::{persistence::rotation_from_array, reparentable::ReparentTarget};
+use glam::{Affine3A, Vec3};
+use serde::{Deserialize, Serialize};
+
+/// Pose and momentum of a [`Grabbable`](super::Grabbable) relative to its content space, to save with the client's state.
+///
+/// Uses the same layout as [`ReparentableState`](crate::reparentable::ReparentableState).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrabbableState {
+	pub translation: [f32; 3],
+	pub rotation: [f32; 4],
+	pub scale: [f32; 3],
+	/// What it was reparented to, only restored if that client still has the spatial exported
+	#[serde(default)]
+	pub parent: Option<ReparentTarget>,
+	pub linear_velocity: Option<[f32; 3]>,
+	/// Axis and angle per second
+	pub angular_velocity: Option<([f32; 3], f32)>,
+}
+impl GrabbableState {
+	pub(crate) fn new(
+		pose: Affine3A,
+		linear_velocity: Option<Vec3>,
+		angular_velocity: Option<(Vec3, f32)>,
+		parent: Option<ReparentTarget>,
+	) -> Self {
+		let (scale, rotation, translation) = pose.to_scale_rotation_translation();
+		GrabbableState {
+			translation: translation.into(),
+			rotation: rotation.into(),
+			scale: scale.into(),
+			parent,
+			linear_velocity: linear_velocity.map(Into::into),
+			angular_velocity: angular_velocity.map(|(axis, angle)| (axis.into(), angle)),
+		}
+	}
+	pub(crate) fn pose(&self) -> Affine3A {
+		Affine3A::from_scale_rotation_translation(
+			self.scale.into(),
+			rotation_from_array(self.rotation),
+			self.translation.into(),
+		)
+	}
+}
+
+#[test]
+fn grabbable_state_roundtrip() {
+	use glam::Quat;
+
+	let pose = Affine3A::from_scale_rotation_translation(
+		Vec3::splat(2.0),
+		Quat::from_rotation_y(0.5),
+		Vec3::new(0.1, 0.2, 0.3),
+	);
+	let state = GrabbableState::new(
+		pose,
+		Some(Vec3::X),
+		None,
+		Some(ReparentTarget {
+			client: ":1.42".to_string(),
+			spatial: 7,
+		}),
+	);
+	let json = serde_json::to_string(&state).unwrap();
+	let restored: GrabbableState = serde_json::from_str(&json).unwrap();
+	assert_eq!(restored, state);
+	assert!(restored.pose().abs_diff_eq(pose, 0.0001));
+}
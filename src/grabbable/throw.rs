@@ -0,0 +1,158 @@
+use super::GrabbableConstraints;
+use crate::zone::Zone;
+use futures_util::future::join_all;
+use glam::Vec3;
+use stardust_xr_fusion::{fields::FieldRefAspect, spatial::SpatialRefAspect, values::Vector3};
+use std::sync::Arc;
+
+/// Past this the grabbable has moved 95% of the way to where it stops
+const SETTLE_TIME_CONSTANTS: f32 = 3.0;
+/// How far ahead to look when there's no drag to stop the grabbable
+const MAX_THROW_TIME: f32 = 2.0;
+
+/// Where a thrown grabbable is going to go, assuming nothing gets in its way.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThrowPrediction {
+	pub origin: Vector3<f32>,
+	pub velocity: Vector3<f32>,
+	/// Linear momentum drag
+	pub drag: f32,
+}
+impl ThrowPrediction {
+	pub fn position_at(&self, time: f32) -> Vector3<f32> {
+		let origin = Vec3::from(self.origin);
+		let velocity = Vec3::from(self.velocity);
+		if self.drag <= 0.0 {
+			return (origin + velocity * time).into();
+		}
+		(origin + velocity / self.drag * (1.0 - (-self.drag * time).exp())).into()
+	}
+	/// Where it comes to a stop
+	pub fn rest_position(&self) -> Vector3<f32> {
+		if self.drag <= 0.0 {
+			return self.position_at(MAX_THROW_TIME);
+		}
+		(Vec3::from(self.origin) + Vec3::from(self.velocity) / self.drag).into()
+	}
+	/// `samples` points evenly spaced in time from the origin until it's practically stopped
+	pub fn trajectory(&self, samples: usize) -> Vec<Vector3<f32>> {
+		let duration = if self.drag > 0.0 {
+			SETTLE_TIME_CONSTANTS / self.drag
+		} else {
+			MAX_THROW_TIME
+		};
+		let samples = samples.max(2);
+		(0..samples)
+			.map(|i| self.position_at(duration * i as f32 / (samples - 1) as f32))
+			.collect()
+	}
+	/// First zone along the trajectory and where it enters it, `space` is the space the prediction is in.
+	pub async fn find_zone<'a>(
+		&self,
+		space: &impl SpatialRefAspect,
+		zones: &'a [Arc<Zone>],
+		samples: usize,
+	) -> Option<(&'a Arc<Zone>, Vector3<f32>)> {
+		for point in self.trajectory(samples) {
+			let distances =
+				join_all(zones.iter().map(|zone| zone.field().distance(space, point))).await;
+			let hit = zones
+				.iter()
+				.zip(distances)
+				.find(|(zone, distance)| distance.as_ref().is_ok_and(|d| *d <= zone.margin()));
+			if let Some((zone, _)) = hit {
+				return Some((zone, point));
+			}
+		}
+		None
+	}
+}
+
+/// Close enough to the steering target to count as there
+const STEER_ARRIVAL_DISTANCE: f32 = 0.001;
+
+/// One frame of steering a thrown grabbable towards where it's meant to land.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum SteerStep {
+	Moving {
+		position: Vec3,
+		velocity: Vec3,
+	},
+	/// Stop steering and rest here, either at the target or as close as the constraints allow
+	Arrived(Vec3),
+}
+/// Ease towards `target` every frame, plain drag would stop it short.
+///
+/// The target gets constrained first, and if a step doesn't get any closer the constraints are in the way so it stops there.
+pub(crate) fn steer_step(
+	position: Vec3,
+	target: Vec3,
+	constraints: &GrabbableConstraints,
+	drag: f32,
+	delta: f32,
+) -> SteerStep {
+	let target = constraints.constrain_translation(target);
+	let offset = target - position;
+	let distance = offset.length();
+	if distance < STEER_ARRIVAL_DISTANCE {
+		return SteerStep::Arrived(target);
+	}
+	let next =
+		constraints.constrain_translation(position + offset * (drag * delta).clamp(0.0, 1.0));
+	if next.distance(target) >= distance {
+		return SteerStep::Arrived(position);
+	}
+	SteerStep::Moving {
+		position: next,
+		velocity: offset * drag,
+	}
+}
+
+#[test]
+fn throw_prediction_rest_position() {
+	let prediction = ThrowPrediction {
+		origin: [0.0, 1.0, 0.0].into(),
+		velocity: [2.0, 0.0, 0.0].into(),
+		drag: 8.0,
+	};
+	assert!(Vec3::from(prediction.rest_position()).abs_diff_eq(Vec3::new(0.25, 1.0, 0.0), 0.0001));
+	let trajectory = prediction.trajectory(8);
+	assert_eq!(trajectory.len(), 8);
+	assert_eq!(trajectory[0], prediction.origin);
+	assert!(trajectory.windows(2).all(|w| w[1].x > w[0].x));
+	assert!(trajectory[7].x < 0.25 && trajectory[7].x > 0.2);
+}
+
+#[test]
+fn steer_into_out_of_bounds_zone() {
+	use super::{Bounds, TranslationConstraint};
+
+	let constraints = GrabbableConstraints {
+		translation: TranslationConstraint::Plane {
+			origin: [0.0; 3].into(),
+			normal: [0.0, 1.0, 0.0].into(),
+		},
+		bounds: Some(Bounds {
+			min: [-0.5; 3].into(),
+			max: [0.5; 3].into(),
+		}),
+		..Default::default()
+	};
+	// the zone is above the plane and past the bounds
+	let target = Vec3::new(1.0, 0.3, 0.0);
+	let mut position = Vec3::ZERO;
+	let mut arrived = None;
+	for _ in 0..1000 {
+		match steer_step(position, target, &constraints, 8.0, 1.0 / 60.0) {
+			SteerStep::Moving { position: next, .. } => {
+				position = constraints.constrain_translation(next);
+			}
+			SteerStep::Arrived(rest) => {
+				arrived = Some(rest);
+				break;
+			}
+		}
+	}
+	let arrived = arrived.expect("steering never arrived");
+	assert!(arrived.abs_diff_eq(Vec3::new(0.5, 0.0, 0.0), 0.01));
+}
@@ -1,6 +1,9 @@
 use crate::{
 	DebugSettings, VisualDebug,
-	input_action::{DeltaSet, InputQueue, InputQueueable, SingleAction},
+	input_action::{
+		DeltaSet, Gesture, GestureRecognizer, GestureSettings, InputQueue, InputQueueable,
+		InteractCondition, InteractConditionSettings, InteractThreshold, SingleAction,
+	},
 	lines::{self, LineExt},
 };
 use glam::{Mat4, Vec3, vec3};
@@ -27,6 +30,8 @@ pub struct HoverPlaneSettings {
 	pub line_end_thickness: f32,
 	pub line_end_color_hover: Rgba<f32, LinearRgb>,
 	pub line_end_color_interact: Rgba<f32, LinearRgb>,
+	pub interact_condition: InteractConditionSettings,
+	pub gestures: GestureSettings,
 }
 impl Default for HoverPlaneSettings {
 	fn default() -> Self {
@@ -38,6 +43,15 @@ impl Default for HoverPlaneSettings {
 			line_end_thickness: 0.005,
 			line_end_color_hover: rgba_linear!(1.0, 1.0, 1.0, 0.0),
 			line_end_color_interact: rgba_linear!(0.0, 1.0, 0.75, 0.0),
+			interact_condition: InteractConditionSettings {
+				hand: InteractThreshold {
+					key: "pinch_strength",
+					engage: 0.95,
+					release: 0.85,
+				},
+				..InteractConditionSettings::select()
+			},
+			gestures: GestureSettings::default(),
 		}
 	}
 }
@@ -46,7 +60,9 @@ pub struct HoverPlane {
 	root: Spatial,
 	input: InputQueue,
 	field: Field,
+	interact_condition: InteractCondition,
 	interact: SingleAction,
+	gestures: GestureRecognizer,
 	size: Vector2<f32>,
 	pub x_range: Range<f32>,
 	pub y_range: Range<f32>,
@@ -81,7 +97,9 @@ impl HoverPlane {
 			root,
 			input,
 			field,
+			interact_condition: InteractCondition::default(),
 			interact: interact_action,
+			gestures: GestureRecognizer::default(),
 			size,
 			x_range,
 			y_range,
@@ -138,6 +156,10 @@ impl HoverPlane {
 	pub fn input_queue(&self) -> &InputQueue {
 		&self.input
 	}
+	/// Mainly useful for recording or replaying input
+	pub fn input_queue_mut(&mut self) -> &mut InputQueue {
+		&mut self.input
+	}
 	pub fn field(&self) -> &Field {
 		&self.field
 	}
@@ -171,6 +193,10 @@ impl HoverPlane {
 	pub fn interact_status(&self) -> &SingleAction {
 		&self.interact
 	}
+	/// Gestures from the last update, positions are local to the root
+	pub fn gestures(&self) -> &[Gesture] {
+		self.gestures.gestures()
+	}
 
 	/// Set whether this will receive input or not
 	pub fn set_enabled(&self, enabled: bool) -> Result<(), NodeError> {
@@ -178,8 +204,15 @@ impl HoverPlane {
 	}
 
 	/// Update the state of this touch plane. Run once every frame.
-	pub fn update(&mut self) {
-		self.input.handle_events();
+	///
+	/// Returns true if there were input events to handle or a held input reached the hold time.
+	pub fn update(&mut self) -> bool {
+		let hold_elapsed = self
+			.interact_condition
+			.update_hold(&self.settings.interact_condition);
+		let handled = self.input.handle_events() || hold_elapsed;
+		self.interact_condition
+			.update(&self.input, &self.settings.interact_condition);
 		self.interact.update(
 			false,
 			&self.input,
@@ -193,12 +226,12 @@ impl HoverPlane {
 						&& Self::hover(self.size, interact_point.into(), true)
 				}
 			},
-			|input| match &input.input {
-				InputDataType::Hand(_) => input
-					.datamap
-					.with_data(|d| d.idx("pinch_strength").as_f32() > 0.95),
-				_ => input.datamap.with_data(|d| d.idx("select").as_f32() > 0.9),
-			},
+			self.interact_condition.condition(),
+		);
+		self.gestures.update_single(
+			&self.interact,
+			&self.settings.gestures,
+			Self::interact_point_local,
 		);
 
 		let mut hovered_lines = self
@@ -213,6 +246,7 @@ impl HoverPlane {
 			hovered_lines.push(line);
 		}
 		self.lines.set_lines(&hovered_lines).unwrap();
+		handled
 	}
 
 	fn line_from_input(&self, input: &InputData, interacting: bool) -> Option<Line> {
@@ -0,0 +1,85 @@
+use futures_util::{Stream, stream};
+use stardust_xr_fusion::input::InputData;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+/// A single change to the input of an [`InputQueue`](super::InputQueue).
+#[derive(Debug, Clone)]
+pub enum InputChange {
+	Added(Arc<InputData>),
+	Updated(Arc<InputData>),
+	Removed(Arc<InputData>),
+}
+impl InputChange {
+	pub fn data(&self) -> &Arc<InputData> {
+		match self {
+			InputChange::Added(data) => data,
+			InputChange::Updated(data) => data,
+			InputChange::Removed(data) => data,
+		}
+	}
+}
+
+/// Receives every [`InputChange`] an [`InputQueue`](super::InputQueue) handles after subscribing.
+pub struct InputChanges(pub(crate) broadcast::Receiver<InputChange>);
+impl InputChanges {
+	/// Wait for the next change, returns `None` once the queue has been dropped.
+	pub async fn recv(&mut self) -> Option<InputChange> {
+		loop {
+			match self.0.recv().await {
+				Ok(change) => return Some(change),
+				Err(broadcast::error::RecvError::Lagged(skipped)) => {
+					warn!(skipped, "input change receiver lagged behind");
+				}
+				Err(broadcast::error::RecvError::Closed) => return None,
+			}
+		}
+	}
+	/// Wait until anything about the input changed, skipping over the individual changes.
+	pub async fn changed(&mut self) -> bool {
+		let changed = self.recv().await.is_some();
+		while let Ok(_) | Err(broadcast::error::TryRecvError::Lagged(_)) = self.0.try_recv() {}
+		changed
+	}
+	/// Like [`InputChanges::recv`], the queue still has to be handled elsewhere for this to yield anything
+	pub fn into_stream(self) -> impl Stream<Item = InputChange> {
+		stream::unfold(self, |mut changes| async move {
+			let change = changes.recv().await?;
+			Some((change, changes))
+		})
+	}
+}
+
+#[tokio::test]
+async fn input_changes_stream() {
+	use super::{
+		InputQueue,
+		mock::{MockDatamap, MockInputHandler, mock_input_data, mock_tip},
+	};
+	use futures_util::StreamExt;
+
+	let tip = |distance| {
+		mock_input_data(
+			2,
+			mock_tip([0.0; 3], [0.0, 0.0, 0.0, 1.0]),
+			distance,
+			MockDatamap::default(),
+			false,
+		)
+	};
+	let mut queue = InputQueue::new(MockInputHandler::default());
+	let changes = queue.subscribe().into_stream();
+
+	queue.backend_mut().send(5, tip(0.1));
+	queue.backend_mut().update(tip(0.0));
+	queue.backend_mut().leave(5);
+	queue.wait_for_update(|| async {}).await;
+	drop(queue);
+
+	let changes = changes.collect::<Vec<_>>().await;
+	assert!(matches!(changes[0], InputChange::Added(_)));
+	assert!(matches!(&changes[1], InputChange::Updated(data) if data.distance == 0.0));
+	assert!(matches!(changes[2], InputChange::Removed(_)));
+	assert_eq!(changes.len(), 3);
+}
@@ -0,0 +1,252 @@
+use super::{DeltaSet, InputBackend, InputQueue, MultiAction};
+use glam::{Affine3A, Quat, Vec3};
+use rustc_hash::FxHashSet;
+use stardust_xr_fusion::input::InputData;
+use std::sync::Arc;
+
+/// Change in the pose of 2 inputs relative to each other.
+///
+/// Rotation only comes from the line between the inputs, so twisting around that line isn't included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DualTransform {
+	/// Midpoint between the inputs before the change, rotation and scale happen around this
+	pub pivot: Vec3,
+	/// How much the midpoint moved
+	pub translation: Vec3,
+	pub rotation: Quat,
+	/// Ratio between the new and old distance between the inputs
+	pub scale: f32,
+}
+impl DualTransform {
+	pub const IDENTITY: Self = DualTransform {
+		pivot: Vec3::ZERO,
+		translation: Vec3::ZERO,
+		rotation: Quat::IDENTITY,
+		scale: 1.0,
+	};
+
+	fn between(from: DualFrame, to: DualFrame) -> Self {
+		let rotation = if from.direction == Vec3::ZERO || to.direction == Vec3::ZERO {
+			Quat::IDENTITY
+		} else {
+			Quat::from_rotation_arc(from.direction, to.direction)
+		};
+		let scale = if from.distance > f32::EPSILON {
+			to.distance / from.distance
+		} else {
+			1.0
+		};
+		DualTransform {
+			pivot: from.midpoint,
+			translation: to.midpoint - from.midpoint,
+			rotation,
+			scale,
+		}
+	}
+
+	/// Apply this to anything in the same space as the inputs to follow them
+	pub fn matrix(&self) -> Affine3A {
+		Affine3A::from_translation(self.pivot + self.translation)
+			* Affine3A::from_scale_rotation_translation(
+				Vec3::splat(self.scale),
+				self.rotation,
+				Vec3::ZERO,
+			) * Affine3A::from_translation(-self.pivot)
+	}
+}
+impl Default for DualTransform {
+	fn default() -> Self {
+		Self::IDENTITY
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DualFrame {
+	midpoint: Vec3,
+	direction: Vec3,
+	distance: f32,
+}
+impl DualFrame {
+	fn new(a: Vec3, b: Vec3) -> Self {
+		let offset = b - a;
+		DualFrame {
+			midpoint: (a + b) * 0.5,
+			direction: offset.normalize_or_zero(),
+			distance: offset.length(),
+		}
+	}
+}
+
+/// Tracks 2 inputs interacting at the same time, e.g. for two-handed grabbing, stretching and rotating.
+#[derive(Default, Debug)]
+pub struct DualAction {
+	multi: MultiAction,
+	actors: [Option<Arc<InputData>>; 2],
+
+	started: bool,
+	acting: bool,
+	stopped: bool,
+
+	start_frame: Option<DualFrame>,
+	last_frame: Option<DualFrame>,
+	delta: DualTransform,
+	total: DualTransform,
+}
+impl DualAction {
+	/// `position` gets the point of each input that should be followed, such as the pinch point.
+	pub fn update<B: InputBackend>(
+		&mut self,
+		queue: &InputQueue<B>,
+		hover_condition: impl Fn(&InputData) -> bool,
+		interact_condition: impl Fn(&InputData) -> bool,
+		position: impl Fn(&InputData) -> Vec3,
+	) {
+		self.multi
+			.update(queue, hover_condition, interact_condition);
+		let interacting = self.multi.interact().current().clone();
+		self.update_from(&interacting, position);
+	}
+	/// Follow inputs another action already captured, e.g. [`super::SingleAction::interacting`], so they aren't captured twice.
+	///
+	/// [`DualAction::hovering`] and [`DualAction::interacting`] stay empty when updated this way.
+	pub fn update_from(
+		&mut self,
+		interacting: &FxHashSet<Arc<InputData>>,
+		position: impl Fn(&InputData) -> Vec3,
+	) {
+		// refresh the actors' data and drop the ones that stopped interacting
+		let mut changed = false;
+		for actor in &mut self.actors {
+			let Some(old_actor) = actor.take() else {
+				continue;
+			};
+			*actor = interacting.get(&old_actor).cloned();
+			changed |= actor.is_none();
+		}
+		let mut new_actors = interacting
+			.iter()
+			.filter(|i| !self.actors.iter().flatten().any(|a| a == *i))
+			.cloned()
+			.collect::<Vec<_>>();
+		new_actors.sort_by_key(|i| i.id);
+		let mut new_actors = new_actors.into_iter();
+		for actor in self.actors.iter_mut().filter(|a| a.is_none()) {
+			*actor = new_actors.next();
+			changed |= actor.is_some();
+		}
+
+		let was_acting = self.acting;
+		self.acting = self.actors.iter().all(Option::is_some);
+		self.started = self.acting && (!was_acting || changed);
+		self.stopped = was_acting && !self.acting;
+
+		let frame = match &self.actors {
+			[Some(a), Some(b)] => Some(DualFrame::new((position)(a), (position)(b))),
+			_ => None,
+		};
+		match (frame, self.start_frame, self.last_frame) {
+			(Some(frame), Some(start_frame), Some(last_frame)) if !self.started => {
+				self.delta = DualTransform::between(last_frame, frame);
+				self.total = DualTransform::between(start_frame, frame);
+				self.last_frame.replace(frame);
+			}
+			(frame, _, _) => {
+				self.start_frame = frame;
+				self.last_frame = frame;
+				self.delta = DualTransform::IDENTITY;
+				self.total = DualTransform::IDENTITY;
+			}
+		}
+	}
+
+	pub fn hovering(&self) -> &DeltaSet<Arc<InputData>> {
+		self.multi.hover()
+	}
+	pub fn interacting(&self) -> &DeltaSet<Arc<InputData>> {
+		self.multi.interact()
+	}
+	/// The first input to start interacting
+	pub fn primary(&self) -> Option<&Arc<InputData>> {
+		self.actors[0].as_ref()
+	}
+	pub fn secondary(&self) -> Option<&Arc<InputData>> {
+		self.actors[1].as_ref()
+	}
+
+	/// True when both inputs just started interacting, or one of them was replaced
+	pub fn started(&self) -> bool {
+		self.started
+	}
+	pub fn acting(&self) -> bool {
+		self.acting
+	}
+	pub fn stopped(&self) -> bool {
+		self.stopped
+	}
+
+	/// Change since the last update
+	pub fn delta(&self) -> DualTransform {
+		self.delta
+	}
+	/// Change since both inputs started interacting
+	pub fn total(&self) -> DualTransform {
+		self.total
+	}
+}
+
+#[test]
+fn dual_action_scale() {
+	use super::mock::{MockDatamap, MockInputHandler, mock_input_data, mock_tip};
+
+	let tip = |id, x, select| {
+		mock_input_data(
+			id,
+			mock_tip([x, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0]),
+			0.0,
+			MockDatamap {
+				select,
+				..Default::default()
+			},
+			true,
+		)
+	};
+	fn step(queue: &mut InputQueue<MockInputHandler>, action: &mut DualAction) {
+		queue.handle_events();
+		action.update(
+			queue,
+			|_| true,
+			|d| d.datamap.with_data(|d| d.idx("select").as_f32() > 0.5),
+			|d| match &d.input {
+				stardust_xr_fusion::input::InputDataType::Tip(t) => t.origin.into(),
+				_ => Vec3::ZERO,
+			},
+		);
+	}
+	let mut queue = InputQueue::new(MockInputHandler::default());
+	let mut action = DualAction::default();
+
+	queue.backend_mut().send(1, tip(1, -0.1, 0.0));
+	queue.backend_mut().send(2, tip(2, 0.1, 0.0));
+	step(&mut queue, &mut action);
+	queue.backend_mut().update(tip(1, -0.1, 0.0));
+	step(&mut queue, &mut action);
+	assert!(!action.acting());
+
+	queue.backend_mut().update(tip(1, -0.1, 1.0));
+	queue.backend_mut().update(tip(2, 0.1, 1.0));
+	step(&mut queue, &mut action);
+	assert!(action.started());
+	assert_eq!(action.primary().unwrap().id, 1);
+
+	queue.backend_mut().update(tip(1, -0.2, 1.0));
+	queue.backend_mut().update(tip(2, 0.2, 1.0));
+	step(&mut queue, &mut action);
+	assert!(!action.started());
+	assert!((action.total().scale - 2.0).abs() < 0.0001);
+	assert!(action.total().translation.length() < 0.0001);
+
+	queue.backend_mut().update(tip(2, 0.2, 0.0));
+	step(&mut queue, &mut action);
+	assert!(action.stopped());
+	assert_eq!(action.total(), DualTransform::IDENTITY);
+}
@@ -0,0 +1,299 @@
+use super::{MultiAction, SingleAction};
+use glam::Vec3;
+use rustc_hash::FxHashMap;
+use stardust_xr_fusion::{input::InputData, values::Vector3};
+use std::{sync::Arc, time::Instant};
+
+/// Distances are in meters, durations in seconds and speeds in m/s.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureSettings {
+	/// How far the input can move and still count as a tap or long press
+	pub tap_radius: f32,
+	/// Longest a tap can be held
+	pub tap_duration: f32,
+	/// Longest time between the end of 2 taps for them to be a double tap
+	pub double_tap_interval: f32,
+	/// How long to hold before it's a long press
+	pub long_press_duration: f32,
+	/// Minimum distance for a swipe
+	pub swipe_distance: f32,
+	/// Minimum release speed for a flick
+	pub flick_speed: f32,
+}
+impl Default for GestureSettings {
+	fn default() -> Self {
+		Self {
+			tap_radius: 0.01,
+			tap_duration: 0.3,
+			double_tap_interval: 0.3,
+			long_press_duration: 0.6,
+			swipe_distance: 0.05,
+			flick_speed: 0.5,
+		}
+	}
+}
+
+/// Dominant axis of a swipe or flick, in the space the positions were given in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SwipeDirection {
+	Left,
+	Right,
+	Up,
+	Down,
+	/// Towards -Z
+	Forward,
+	/// Towards +Z
+	Backward,
+}
+impl SwipeDirection {
+	fn from_delta(delta: Vec3) -> Self {
+		let abs = delta.abs();
+		if abs.x >= abs.y && abs.x >= abs.z {
+			if delta.x < 0.0 {
+				SwipeDirection::Left
+			} else {
+				SwipeDirection::Right
+			}
+		} else if abs.y >= abs.z {
+			if delta.y < 0.0 {
+				SwipeDirection::Down
+			} else {
+				SwipeDirection::Up
+			}
+		} else if delta.z < 0.0 {
+			SwipeDirection::Forward
+		} else {
+			SwipeDirection::Backward
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub enum Gesture {
+	Tap {
+		input: Arc<InputData>,
+		position: Vector3<f32>,
+	},
+	/// Sent after the second [`Gesture::Tap`]
+	DoubleTap {
+		input: Arc<InputData>,
+		position: Vector3<f32>,
+	},
+	/// Sent once while still held, the release afterwards doesn't make a tap
+	LongPress {
+		input: Arc<InputData>,
+		position: Vector3<f32>,
+	},
+	Swipe {
+		input: Arc<InputData>,
+		direction: SwipeDirection,
+		delta: Vector3<f32>,
+		duration: f32,
+	},
+	Flick {
+		input: Arc<InputData>,
+		direction: SwipeDirection,
+		velocity: Vector3<f32>,
+	},
+}
+
+#[derive(Debug, Clone)]
+struct Touch {
+	input: Arc<InputData>,
+	start_time: Instant,
+	start_position: Vec3,
+	last_time: Instant,
+	last_position: Vec3,
+	velocity: Vec3,
+	max_distance: f32,
+	long_pressed: bool,
+}
+
+/// Turns interactions from an action into taps, long presses, swipes and so on.
+///
+/// Update it after the action with a function that gets each input's position in the space gestures should be in.
+#[derive(Debug, Default)]
+pub struct GestureRecognizer {
+	touches: FxHashMap<u64, Touch>,
+	last_tap: Option<(Instant, Vec3)>,
+	gestures: Vec<Gesture>,
+}
+impl GestureRecognizer {
+	pub fn update_single(
+		&mut self,
+		action: &SingleAction,
+		settings: &GestureSettings,
+		position: impl Fn(&InputData) -> Vec3,
+	) {
+		self.update(action.actor(), settings, position);
+	}
+	pub fn update_multi(
+		&mut self,
+		action: &MultiAction,
+		settings: &GestureSettings,
+		position: impl Fn(&InputData) -> Vec3,
+	) {
+		self.update(action.interact().current(), settings, position);
+	}
+	/// Update with every input that's currently interacting.
+	pub fn update<'a>(
+		&mut self,
+		interacting: impl IntoIterator<Item = &'a Arc<InputData>>,
+		settings: &GestureSettings,
+		position: impl Fn(&InputData) -> Vec3,
+	) {
+		self.update_at(Instant::now(), interacting, settings, position);
+	}
+	fn update_at<'a>(
+		&mut self,
+		now: Instant,
+		interacting: impl IntoIterator<Item = &'a Arc<InputData>>,
+		settings: &GestureSettings,
+		position: impl Fn(&InputData) -> Vec3,
+	) {
+		self.gestures.clear();
+
+		let interacting = interacting.into_iter().collect::<Vec<_>>();
+		let ended = self
+			.touches
+			.keys()
+			.filter(|id| !interacting.iter().any(|i| i.id == **id))
+			.copied()
+			.collect::<Vec<_>>();
+		for id in ended {
+			let touch = self.touches.remove(&id).unwrap();
+			self.end_touch(now, touch, settings);
+		}
+
+		for input in interacting {
+			let point = (position)(input);
+			let Some(touch) = self.touches.get_mut(&input.id) else {
+				self.touches.insert(
+					input.id,
+					Touch {
+						input: input.clone(),
+						start_time: now,
+						start_position: point,
+						last_time: now,
+						last_position: point,
+						velocity: Vec3::ZERO,
+						max_distance: 0.0,
+						long_pressed: false,
+					},
+				);
+				continue;
+			};
+			let delta_time = now.duration_since(touch.last_time).as_secs_f32();
+			if delta_time > 0.0 {
+				let velocity = (point - touch.last_position) / delta_time;
+				touch.velocity = touch.velocity.lerp(velocity, 0.5);
+			}
+			touch.input = input.clone();
+			touch.last_time = now;
+			touch.last_position = point;
+			touch.max_distance = touch.max_distance.max(point.distance(touch.start_position));
+
+			let held_for = now.duration_since(touch.start_time).as_secs_f32();
+			if !touch.long_pressed
+				&& touch.max_distance <= settings.tap_radius
+				&& held_for >= settings.long_press_duration
+			{
+				touch.long_pressed = true;
+				self.gestures.push(Gesture::LongPress {
+					input: input.clone(),
+					position: point.into(),
+				});
+			}
+		}
+	}
+	fn end_touch(&mut self, now: Instant, touch: Touch, settings: &GestureSettings) {
+		let duration = now.duration_since(touch.start_time).as_secs_f32();
+		let delta = touch.last_position - touch.start_position;
+		if touch.long_pressed {
+			return;
+		}
+		if touch.max_distance <= settings.tap_radius && duration <= settings.tap_duration {
+			self.gestures.push(Gesture::Tap {
+				input: touch.input.clone(),
+				position: touch.last_position.into(),
+			});
+			let double_tap = self.last_tap.take().is_some_and(|(time, position)| {
+				now.duration_since(time).as_secs_f32() <= settings.double_tap_interval
+					&& position.distance(touch.last_position) <= settings.tap_radius
+			});
+			if double_tap {
+				self.gestures.push(Gesture::DoubleTap {
+					input: touch.input,
+					position: touch.last_position.into(),
+				});
+			} else {
+				self.last_tap.replace((now, touch.last_position));
+			}
+		} else if touch.velocity.length() >= settings.flick_speed {
+			self.gestures.push(Gesture::Flick {
+				input: touch.input,
+				direction: SwipeDirection::from_delta(touch.velocity),
+				velocity: touch.velocity.into(),
+			});
+		} else if delta.length() >= settings.swipe_distance {
+			self.gestures.push(Gesture::Swipe {
+				input: touch.input,
+				direction: SwipeDirection::from_delta(delta),
+				delta: delta.into(),
+				duration,
+			});
+		}
+	}
+
+	/// Gestures recognized during the last update
+	pub fn gestures(&self) -> &[Gesture] {
+		&self.gestures
+	}
+}
+
+#[test]
+fn gesture_tap_and_swipe() {
+	use super::mock::{MockDatamap, mock_input_data, mock_tip};
+	use std::time::Duration;
+
+	let tip = |x: f32| {
+		Arc::new(mock_input_data(
+			1,
+			mock_tip([x, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0]),
+			0.0,
+			MockDatamap::default(),
+			true,
+		))
+	};
+	let position = |data: &InputData| match &data.input {
+		stardust_xr_fusion::input::InputDataType::Tip(t) => t.origin.into(),
+		_ => Vec3::ZERO,
+	};
+	let settings = GestureSettings::default();
+	let mut recognizer = GestureRecognizer::default();
+	let start = Instant::now();
+	let at = |millis| start + Duration::from_millis(millis);
+
+	recognizer.update_at(at(0), [&tip(0.0)], &settings, position);
+	recognizer.update_at(at(100), [], &settings, position);
+	assert!(matches!(recognizer.gestures(), [Gesture::Tap { .. }]));
+	recognizer.update_at(at(200), [&tip(0.0)], &settings, position);
+	recognizer.update_at(at(300), [], &settings, position);
+	assert!(matches!(
+		recognizer.gestures(),
+		[Gesture::Tap { .. }, Gesture::DoubleTap { .. }]
+	));
+
+	// slow enough to not be a flick
+	recognizer.update_at(at(1000), [&tip(0.0)], &settings, position);
+	recognizer.update_at(at(1500), [&tip(0.05)], &settings, position);
+	recognizer.update_at(at(2000), [&tip(0.1)], &settings, position);
+	recognizer.update_at(at(2500), [], &settings, position);
+	assert!(matches!(
+		recognizer.gestures(),
+		[Gesture::Swipe {
+			direction: SwipeDirection::Right,
+			..
+		}]
+	));
+}
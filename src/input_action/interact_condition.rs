@@ -0,0 +1,215 @@
+use super::{InputBackend, InputQueue};
+use rustc_hash::FxHashMap;
+use stardust_xr_fusion::input::{InputData, InputDataType};
+use std::time::Instant;
+
+/// A datamap value that has to go above `engage` to start interacting and below `release` to stop.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InteractThreshold {
+	pub key: &'static str,
+	pub engage: f32,
+	pub release: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InteractConditionSettings {
+	pub hand: InteractThreshold,
+	pub pointer: InteractThreshold,
+	pub tip: InteractThreshold,
+	/// Seconds the value has to stay above the engage threshold before it counts.
+	pub min_hold_time: f32,
+}
+impl InteractConditionSettings {
+	/// Pinch for hands, `grab` for everything else
+	pub const fn grab() -> Self {
+		Self {
+			hand: InteractThreshold {
+				key: "pinch_strength",
+				engage: 0.9,
+				release: 0.8,
+			},
+			pointer: InteractThreshold {
+				key: "grab",
+				engage: 0.9,
+				release: 0.8,
+			},
+			tip: InteractThreshold {
+				key: "grab",
+				engage: 0.9,
+				release: 0.8,
+			},
+			min_hold_time: 0.0,
+		}
+	}
+	/// Pinch for hands, `select` for everything else
+	pub const fn select() -> Self {
+		Self {
+			hand: InteractThreshold {
+				key: "pinch_strength",
+				engage: 0.9,
+				release: 0.8,
+			},
+			pointer: InteractThreshold {
+				key: "select",
+				engage: 0.9,
+				release: 0.8,
+			},
+			tip: InteractThreshold {
+				key: "select",
+				engage: 0.9,
+				release: 0.8,
+			},
+			min_hold_time: 0.0,
+		}
+	}
+	pub fn threshold(&self, input: &InputDataType) -> &InteractThreshold {
+		match input {
+			InputDataType::Hand(_) => &self.hand,
+			InputDataType::Pointer(_) => &self.pointer,
+			InputDataType::Tip(_) => &self.tip,
+		}
+	}
+	/// Whether the input is above the engage threshold right now, without any hysteresis or hold time
+	pub fn above_engage(&self, data: &InputData) -> bool {
+		let threshold = self.threshold(&data.input);
+		data.datamap
+			.with_data(|datamap| datamap.idx(threshold.key).as_f32() > threshold.engage)
+	}
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct InteractState {
+	engaged: bool,
+	above_since: Option<Instant>,
+}
+
+/// Interaction condition that remembers each input's state so it doesn't flicker around a single threshold.
+///
+/// Update it with the queue before updating the action, then pass [`InteractCondition::condition`] to the action.
+#[derive(Debug, Default)]
+pub struct InteractCondition {
+	states: FxHashMap<u64, InteractState>,
+}
+impl InteractCondition {
+	pub fn update<B: InputBackend>(
+		&mut self,
+		queue: &InputQueue<B>,
+		settings: &InteractConditionSettings,
+	) {
+		let now = Instant::now();
+		let input = queue.input_data();
+		self.states
+			.retain(|id, _| input.iter().any(|data| data.id == *id));
+		for data in input {
+			let threshold = settings.threshold(&data.input);
+			let value = data
+				.datamap
+				.with_data(|datamap| datamap.idx(threshold.key).as_f32());
+			let state = self.states.entry(data.id).or_default();
+			if state.engaged {
+				if value < threshold.release {
+					*state = InteractState::default();
+				}
+			} else if value > threshold.engage {
+				let above_since = *state.above_since.get_or_insert(now);
+				state.engaged =
+					now.duration_since(above_since).as_secs_f32() >= settings.min_hold_time;
+			} else {
+				state.above_since.take();
+			}
+		}
+	}
+	/// Engage inputs that have been above the threshold for `min_hold_time` since the last update, true if any did.
+	///
+	/// This doesn't need new input, so call it every frame to engage inputs that are held still.
+	pub fn update_hold(&mut self, settings: &InteractConditionSettings) -> bool {
+		let now = Instant::now();
+		let mut engaged = false;
+		for state in self.states.values_mut() {
+			if !state.engaged
+				&& let Some(above_since) = state.above_since
+				&& now.duration_since(above_since).as_secs_f32() >= settings.min_hold_time
+			{
+				state.engaged = true;
+				engaged = true;
+			}
+		}
+		engaged
+	}
+	pub fn engaged(&self, data: &InputData) -> bool {
+		self.states.get(&data.id).is_some_and(|state| state.engaged)
+	}
+	pub fn condition(&self) -> impl Fn(&InputData) -> bool + '_ {
+		|data| self.engaged(data)
+	}
+}
+
+#[test]
+fn interact_condition_hysteresis() {
+	use super::mock::{MockDatamap, MockInputHandler, mock_input_data, mock_pointer};
+
+	let pointer = |grab| {
+		mock_input_data(
+			4,
+			mock_pointer([0.0; 3], [0.0, 0.0, 0.0, 1.0]),
+			0.0,
+			MockDatamap {
+				grab,
+				..Default::default()
+			},
+			false,
+		)
+	};
+	let settings = InteractConditionSettings::grab();
+	let mut queue = InputQueue::new(MockInputHandler::default());
+	let mut condition = InteractCondition::default();
+	let mut step = |queue: &mut InputQueue<MockInputHandler>, grab| {
+		queue.backend_mut().update(pointer(grab));
+		queue.handle_events();
+		condition.update(queue, &settings);
+		condition.engaged(&pointer(grab))
+	};
+
+	queue.backend_mut().send(4, pointer(0.0));
+	assert!(!step(&mut queue, 0.85));
+	assert!(step(&mut queue, 0.95));
+	// between the thresholds it stays engaged instead of flickering
+	assert!(step(&mut queue, 0.85));
+	assert!(!step(&mut queue, 0.75));
+	assert!(!step(&mut queue, 0.85));
+}
+
+#[test]
+fn interact_condition_hold_without_events() {
+	use super::mock::{MockDatamap, MockInputHandler, mock_input_data, mock_pointer};
+
+	let pointer = || {
+		mock_input_data(
+			4,
+			mock_pointer([0.0; 3], [0.0, 0.0, 0.0, 1.0]),
+			0.0,
+			MockDatamap {
+				grab: 1.0,
+				..Default::default()
+			},
+			false,
+		)
+	};
+	let settings = InteractConditionSettings {
+		min_hold_time: 0.01,
+		..InteractConditionSettings::grab()
+	};
+	let mut queue = InputQueue::new(MockInputHandler::default());
+	let mut condition = InteractCondition::default();
+
+	queue.backend_mut().send(4, pointer());
+	queue.handle_events();
+	condition.update(&queue, &settings);
+	assert!(!condition.engaged(&pointer()));
+	assert!(!condition.update_hold(&settings));
+
+	std::thread::sleep(std::time::Duration::from_millis(20));
+	// no new events, the hold time passing is enough
+	assert!(condition.update_hold(&settings));
+	assert!(condition.engaged(&pointer()));
+}
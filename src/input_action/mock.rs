@@ -0,0 +1,238 @@
+use super::{InputBackend, InputEvent};
+use serde::Serialize;
+use stardust_xr_fusion::{
+	input::{Finger, Hand, InputData, InputDataType, Joint, Pointer, Thumb, Tip},
+	values::{Datamap, Quaternion, Vector3},
+};
+use std::{collections::VecDeque, sync::Mutex};
+
+/// Input method handle for the [`MockInputHandler`], only identified by its id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MockInputMethod(pub u64);
+
+/// A capture request an [`InputQueue`](super::InputQueue) made to a [`MockInputHandler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CaptureRequest {
+	Capture(u64),
+	Release(u64),
+}
+
+/// Headless input backend so input actions can be tested without a server.
+///
+/// Queue up synthetic events, call `handle_events` on the queue and check which captures were requested.
+#[derive(Debug, Default)]
+pub struct MockInputHandler {
+	events: VecDeque<InputEvent<MockInputMethod>>,
+	capture_requests: Mutex<Vec<CaptureRequest>>,
+}
+impl MockInputHandler {
+	/// An input method started sending input to this handler.
+	pub fn send(&mut self, method: u64, data: InputData) {
+		self.events.push_back(InputEvent::Sent {
+			method: MockInputMethod(method),
+			data,
+		});
+	}
+	/// Input that was already sent has changed.
+	pub fn update(&mut self, data: InputData) {
+		self.events.push_back(InputEvent::Updated { data });
+	}
+	/// An input method stopped sending input to this handler.
+	pub fn leave(&mut self, method: u64) {
+		self.events.push_back(InputEvent::Left { method });
+	}
+	/// All the capture requests made since the last call, in order.
+	pub fn take_capture_requests(&self) -> Vec<CaptureRequest> {
+		std::mem::take(&mut *self.capture_requests.lock().unwrap())
+	}
+}
+impl InputBackend for MockInputHandler {
+	type Method = MockInputMethod;
+
+	fn recv_event(&mut self) -> Option<InputEvent<Self::Method>> {
+		self.events.pop_front()
+	}
+	fn method_id(method: &Self::Method) -> u64 {
+		method.0
+	}
+	fn try_capture(&self, method: &Self::Method) {
+		self.capture_requests
+			.lock()
+			.unwrap()
+			.push(CaptureRequest::Capture(method.0));
+	}
+	fn release(&self, method: &Self::Method) {
+		self.capture_requests
+			.lock()
+			.unwrap()
+			.push(CaptureRequest::Release(method.0));
+	}
+}
+
+/// The datamap fields the widgets in this crate read.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct MockDatamap {
+	pub pinch_strength: f32,
+	pub grab: f32,
+	pub select: f32,
+	pub scroll_continuous: [f32; 2],
+	pub scroll_discrete: [f32; 2],
+}
+
+pub fn mock_input_data(
+	id: u64,
+	input: InputDataType,
+	distance: f32,
+	datamap: MockDatamap,
+	captured: bool,
+) -> InputData {
+	InputData {
+		id,
+		input,
+		distance,
+		datamap: Datamap::from_typed(datamap).unwrap(),
+		order: 0,
+		captured,
+	}
+}
+
+fn mock_joint(position: Vector3<f32>) -> Joint {
+	Joint {
+		position,
+		rotation: Quaternion::from([0.0, 0.0, 0.0, 1.0]),
+		radius: 0.01,
+		distance: 0.0,
+	}
+}
+fn mock_finger(position: Vector3<f32>) -> Finger {
+	Finger {
+		tip: mock_joint(position),
+		distal: mock_joint(position),
+		intermediate: mock_joint(position),
+		proximal: mock_joint(position),
+		metacarpal: mock_joint(position),
+	}
+}
+/// A hand with every joint at `position`, good enough for anything that only looks at tips and the palm.
+pub fn mock_hand(position: impl Into<Vector3<f32>>) -> InputDataType {
+	let position = position.into();
+	InputDataType::Hand(Hand {
+		right: true,
+		thumb: Thumb {
+			tip: mock_joint(position),
+			distal: mock_joint(position),
+			proximal: mock_joint(position),
+			metacarpal: mock_joint(position),
+		},
+		index: mock_finger(position),
+		middle: mock_finger(position),
+		ring: mock_finger(position),
+		little: mock_finger(position),
+		palm: mock_joint(position),
+		wrist: mock_joint(position),
+		elbow: None,
+	})
+}
+pub fn mock_pointer(
+	origin: impl Into<Vector3<f32>>,
+	orientation: impl Into<Quaternion>,
+) -> InputDataType {
+	let origin = origin.into();
+	InputDataType::Pointer(Pointer {
+		origin,
+		orientation: orientation.into(),
+		deepest_point: origin,
+	})
+}
+pub fn mock_tip(
+	origin: impl Into<Vector3<f32>>,
+	orientation: impl Into<Quaternion>,
+) -> InputDataType {
+	InputDataType::Tip(Tip {
+		origin: origin.into(),
+		orientation: orientation.into(),
+	})
+}
+
+#[test]
+fn mock_queue_tracks_input() {
+	use super::InputQueue;
+
+	let mut queue = InputQueue::new(MockInputHandler::default());
+	let hand = |distance| {
+		mock_input_data(
+			1,
+			mock_hand([0.0; 3]),
+			distance,
+			MockDatamap::default(),
+			false,
+		)
+	};
+
+	assert!(!queue.handle_events());
+
+	queue.backend_mut().send(7, hand(0.1));
+	assert!(queue.handle_events());
+	assert_eq!(queue.input().len(), 1);
+
+	queue.backend_mut().update(hand(0.05));
+	assert!(queue.handle_events());
+	assert_eq!(queue.input().keys().next().unwrap().distance, 0.05);
+
+	queue.backend_mut().leave(7);
+	assert!(queue.handle_events());
+	assert!(queue.input().is_empty());
+}
+
+#[test]
+fn mock_single_action_capture() {
+	use super::{InputQueue, SingleAction, grab_pinch_interact};
+
+	fn step(queue: &mut InputQueue<MockInputHandler>, action: &mut SingleAction) {
+		queue.handle_events();
+		action.update(false, queue, |d| d.distance < 0.05, grab_pinch_interact);
+	}
+	let hand = |pinch_strength, captured| {
+		mock_input_data(
+			1,
+			mock_hand([0.0; 3]),
+			0.0,
+			MockDatamap {
+				pinch_strength,
+				..Default::default()
+			},
+			captured,
+		)
+	};
+	let mut queue = InputQueue::new(MockInputHandler::default());
+	let mut action = SingleAction::default();
+
+	// hover for a couple updates so pinching doesn't count as just getting focus
+	queue.backend_mut().send(1, hand(0.0, false));
+	step(&mut queue, &mut action);
+	queue.backend_mut().update(hand(0.0, false));
+	step(&mut queue, &mut action);
+	assert!(queue.backend().take_capture_requests().is_empty());
+
+	queue.backend_mut().update(hand(1.0, false));
+	step(&mut queue, &mut action);
+	assert_eq!(
+		queue.backend().take_capture_requests(),
+		vec![CaptureRequest::Capture(1)]
+	);
+	// not an actor until the capture has actually been granted
+	assert!(!action.actor_acting());
+
+	queue.backend_mut().update(hand(1.0, true));
+	step(&mut queue, &mut action);
+	assert!(action.actor_started());
+	assert_eq!(action.actor().unwrap().id, 1);
+
+	queue.backend_mut().update(hand(0.0, true));
+	step(&mut queue, &mut action);
+	assert!(action.actor_stopped());
+	assert_eq!(
+		queue.backend().take_capture_requests(),
+		vec![CaptureRequest::Release(1)]
+	);
+}
@@ -5,12 +5,27 @@ mod simple_action;
 pub use simple_action::*;
 mod multi_action;
 pub use multi_action::*;
+mod dual_action;
+pub use dual_action::*;
+/// Fake input backend and input data for testing actions without a server
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+mod recording;
+pub use recording::*;
+mod changes;
+pub use changes::*;
+mod interact_condition;
+pub use interact_condition::*;
+mod gesture;
+pub use gesture::*;
 
+use crate::UIElement;
 use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
 use stardust_xr_fusion::{
 	input::{
-		InputData, InputDataType, InputHandler, InputHandlerAspect, InputHandlerEvent,
-		InputMethodRef, InputMethodRefAspect, Pointer,
+		InputData, InputHandler, InputHandlerAspect, InputHandlerEvent, InputMethodRef,
+		InputMethodRefAspect, Pointer,
 	},
 	node::{NodeResult, NodeType},
 	values::Vector3,
@@ -20,75 +35,261 @@ use std::{
 	hash::Hash,
 	sync::Arc,
 };
+use tokio::sync::broadcast;
+
+/// Where an [`InputQueue`] gets its input events from and sends its capture requests to.
+pub trait InputBackend {
+	/// Handle to the input method that sent a piece of input, used for capturing.
+	type Method;
+
+	/// Get the next pending event, if any.
+	fn recv_event(&mut self) -> Option<InputEvent<Self::Method>>;
+	fn method_id(method: &Self::Method) -> u64;
+	fn try_capture(&self, method: &Self::Method);
+	fn release(&self, method: &Self::Method);
+}
+impl InputBackend for InputHandler {
+	type Method = InputMethodRef;
+
+	fn recv_event(&mut self) -> Option<InputEvent<Self::Method>> {
+		Some(match self.recv_input_handler_event()? {
+			InputHandlerEvent::InputSent { method, data } => InputEvent::Sent { method, data },
+			InputHandlerEvent::InputUpdated { data } => InputEvent::Updated { data },
+			InputHandlerEvent::InputLeft { method } => InputEvent::Left { method },
+		})
+	}
+	fn method_id(method: &Self::Method) -> u64 {
+		method.id()
+	}
+	fn try_capture(&self, method: &Self::Method) {
+		let _ = method.try_capture(self);
+	}
+	fn release(&self, method: &Self::Method) {
+		let _ = method.release(self);
+	}
+}
+
+/// Backend agnostic version of [`InputHandlerEvent`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum InputEvent<M> {
+	Sent { method: M, data: InputData },
+	Updated { data: InputData },
+	Left { method: u64 },
+}
 
 pub trait InputQueueable: Sized {
 	fn queue(self) -> NodeResult<InputQueue>;
 }
 impl InputQueueable for InputHandler {
 	fn queue(self) -> NodeResult<InputQueue> {
-		Ok(InputQueue {
-			handler: self,
-			input: FxHashMap::default(),
-		})
+		Ok(InputQueue::new(self))
 	}
 }
 
-pub struct InputQueue {
-	handler: InputHandler,
-	input: FxHashMap<Arc<InputData>, InputMethodRef>,
+pub struct InputQueue<B: InputBackend = InputHandler> {
+	backend: B,
+	input: FxHashMap<Arc<InputData>, B::Method>,
+	recorder: Option<InputRecorder>,
+	replay: Option<(InputReplay, FxHashMap<Arc<InputData>, u64>)>,
+	/// Whichever of the live or replayed input is in use, kept so actions don't collect it every update
+	input_data: FxHashSet<Arc<InputData>>,
+	changes: broadcast::Sender<InputChange>,
 }
 impl InputQueue {
 	pub fn handler(&self) -> &InputHandler {
-		&self.handler
+		&self.backend
 	}
-	pub fn input(&self) -> FxHashMap<Arc<InputData>, &InputMethodRef> {
+}
+impl<B: InputBackend> InputQueue<B> {
+	pub fn new(backend: B) -> Self {
+		InputQueue {
+			backend,
+			input: FxHashMap::default(),
+			recorder: None,
+			replay: None,
+			input_data: FxHashSet::default(),
+			changes: broadcast::channel(64).0,
+		}
+	}
+	pub fn backend(&self) -> &B {
+		&self.backend
+	}
+	pub fn backend_mut(&mut self) -> &mut B {
+		&mut self.backend
+	}
+	/// The live input and the methods it came from.
+	/// This keeps tracking live input during a replay, [`InputQueue::input_data`] is what actions see.
+	pub fn input(&self) -> FxHashMap<Arc<InputData>, &B::Method> {
 		FxHashMap::from_iter(self.input.iter().map(|(i, m)| (i.clone(), m)))
 	}
+	/// The input that actions should use, which is the replayed input while replaying.
+	pub fn input_data(&self) -> &FxHashSet<Arc<InputData>> {
+		&self.input_data
+	}
+	fn refresh_input_data(&mut self) {
+		self.input_data = match &self.replay {
+			Some((_, replayed)) => replayed.keys().cloned().collect(),
+			None => self.input.keys().cloned().collect(),
+		};
+	}
 	pub fn start_capture(&self, data: &Arc<InputData>) {
-		let input = self.input();
-		let Some(method) = input.get(data) else {
+		if self.replay.is_some() {
+			return;
+		}
+		let Some(method) = self.input.get(data) else {
 			return;
 		};
-		let _ = method.try_capture(self.handler());
+		self.backend.try_capture(method);
 	}
 	pub fn release_capture(&self, data: &Arc<InputData>) {
-		let input = self.input();
-		let Some(method) = input.get(data) else {
+		if self.replay.is_some() {
+			return;
+		}
+		let Some(method) = self.input.get(data) else {
+			return;
+		};
+		self.backend.release(method);
+	}
+
+	/// Record every event this queue receives from now on until [`InputQueue::stop_recording`].
+	pub fn start_recording(&mut self) {
+		self.recorder.replace(InputRecorder::new());
+	}
+	pub fn stop_recording(&mut self) -> Option<InputRecording> {
+		self.recorder.take().map(InputRecorder::finish)
+	}
+
+	/// Replace the live input with a recording, one batch per `handle_events` call.
+	///
+	/// Live input is still tracked in the background so it's up to date once the replay is over.
+	pub fn start_replay(&mut self, recording: InputRecording) {
+		self.stop_replay();
+		self.send_changes(
+			self.input
+				.keys()
+				.cloned()
+				.map(InputChange::Removed)
+				.collect(),
+		);
+		self.replay
+			.replace((InputReplay::new(recording), FxHashMap::default()));
+		self.refresh_input_data();
+	}
+	pub fn stop_replay(&mut self) {
+		let Some((_, replayed)) = self.replay.take() else {
 			return;
 		};
-		let _ = method.release(self.handler());
+		let changes = replayed
+			.into_keys()
+			.map(InputChange::Removed)
+			.chain(self.input.keys().cloned().map(InputChange::Added));
+		self.send_changes(changes.collect());
+		self.refresh_input_data();
+	}
+	pub fn replaying(&self) -> bool {
+		self.replay.is_some()
+	}
+
+	/// Get every change to the input from now on, as it's handled.
+	///
+	/// Changes only come in while something calls [`InputQueue::handle_events`], this doesn't handle them on its own.
+	pub fn subscribe(&self) -> InputChanges {
+		InputChanges(self.changes.subscribe())
 	}
 
 	// check this as often as possible, will return true when input has been updated
 	pub fn handle_events(&mut self) -> bool {
+		let mut events = Vec::new();
+		// TODO: truly async input handling but this queueing will do for now
+		while let Some(event) = self.backend.recv_event() {
+			events.push(event);
+		}
+		if let Some(recorder) = &mut self.recorder {
+			recorder.record::<B>(&events);
+		}
+		let mut live_changes = Vec::new();
 		let mut updated = false;
+		for event in events {
+			updated |= apply_event(&mut self.input, event, B::method_id, &mut live_changes);
+		}
 
-		// TODO: truly async input handling but this queueing will do for now
-		while let Some(event) = self.handler.recv_input_handler_event() {
-			match event {
-				InputHandlerEvent::InputSent { method, data } => {
-					updated = true;
-					self.input.insert(Arc::new(data), method);
-				}
-				InputHandlerEvent::InputUpdated { data } => {
-					let Some(method) = self.input.remove(&data) else {
-						continue;
-					};
-					updated = true;
-					self.input.insert(Arc::new(data), method.clone());
-				}
-				InputHandlerEvent::InputLeft { method } => {
-					updated = true;
-					self.input.retain(|_, i| i.id() != method);
-				}
+		let Some((replay, replayed)) = &mut self.replay else {
+			self.send_changes(live_changes);
+			if updated {
+				self.refresh_input_data();
 			}
+			return updated;
+		};
+		if !replay.advance() {
+			self.stop_replay();
+			// the replayed input all goes away at once, so the live input has to count as an update
+			return true;
+		}
+		let mut replay_changes = Vec::new();
+		let mut updated = false;
+		while let Some(event) = replay.recv_event() {
+			updated |= apply_event(replayed, event, InputReplay::method_id, &mut replay_changes);
+		}
+		self.send_changes(replay_changes);
+		if updated {
+			self.refresh_input_data();
 		}
 		updated
 	}
+	fn send_changes(&self, changes: Vec<InputChange>) {
+		for change in changes {
+			// no subscribers is fine
+			let _ = self.changes.send(change);
+		}
+	}
+
+	/// Handle events until the input has actually been updated, without polling every frame.
+	///
+	/// `wait` should resolve when new events might have arrived, such as the client's async event handle.
+	pub async fn wait_for_update<F: Future<Output = ()>>(&mut self, wait: impl FnMut() -> F) {
+		UIElement::wait_for_update(self, wait).await
+	}
+}
+impl<B: InputBackend> UIElement for InputQueue<B> {
+	fn handle_events(&mut self) -> bool {
+		InputQueue::handle_events(self)
+	}
+}
+fn apply_event<M>(
+	input: &mut FxHashMap<Arc<InputData>, M>,
+	event: InputEvent<M>,
+	method_id: impl Fn(&M) -> u64,
+	changes: &mut Vec<InputChange>,
+) -> bool {
+	match event {
+		InputEvent::Sent { method, data } => {
+			let data = Arc::new(data);
+			input.insert(data.clone(), method);
+			changes.push(InputChange::Added(data));
+		}
+		InputEvent::Updated { data } => {
+			let Some(method) = input.remove(&data) else {
+				return false;
+			};
+			let data = Arc::new(data);
+			input.insert(data.clone(), method);
+			changes.push(InputChange::Updated(data));
+		}
+		InputEvent::Left { method } => {
+			input.retain(|data, i| {
+				let keep = method_id(i) != method;
+				if !keep {
+					changes.push(InputChange::Removed(data.clone()));
+				}
+				keep
+			});
+		}
+	}
+	true
 }
-impl Debug for InputQueue {
+impl<B: InputBackend> Debug for InputQueue<B> {
 	fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-		self.input().keys().fmt(f)
+		self.input.keys().fmt(f)
 	}
 }
 
@@ -133,17 +334,13 @@ impl<T: Clone + Hash + Eq> DeltaSet<T> {
 	}
 }
 
+/// Stateless version of [`InteractConditionSettings::grab`], use [`InteractCondition`] to avoid flickering
 pub fn grab_pinch_interact(data: &InputData) -> bool {
-	data.datamap.with_data(|datamap| match &data.input {
-		InputDataType::Hand(_) => datamap.idx("pinch_strength").as_f32() > 0.90,
-		_ => datamap.idx("grab").as_f32() > 0.90,
-	})
+	InteractConditionSettings::grab().above_engage(data)
 }
+/// Stateless version of [`InteractConditionSettings::select`], use [`InteractCondition`] to avoid flickering
 pub fn select_pinch_interact(data: &InputData) -> bool {
-	data.datamap.with_data(|datamap| match &data.input {
-		InputDataType::Hand(_) => datamap.idx("pinch_strength").as_f32() > 0.90,
-		_ => datamap.idx("select").as_f32() > 0.90,
-	})
+	InteractConditionSettings::select().above_engage(data)
 }
 
 pub trait PointerExt {
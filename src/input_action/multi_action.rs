@@ -1,4 +1,4 @@
-use super::{DeltaSet, InputQueue, SimpleAction};
+use super::{DeltaSet, InputBackend, InputQueue, SimpleAction};
 use stardust_xr_fusion::input::InputData;
 use std::sync::Arc;
 
@@ -9,14 +9,24 @@ pub struct MultiAction {
 	interact: DeltaSet<Arc<InputData>>,
 }
 impl MultiAction {
-	pub fn update(
+	pub fn update<B: InputBackend>(
 		&mut self,
-		queue: &InputQueue,
+		queue: &InputQueue<B>,
 		hover_condition: impl Fn(&InputData) -> bool,
 		interact_condition: impl Fn(&InputData) -> bool,
 	) {
-		let input = queue.input();
-		let hover_action = input.keys().filter(|d| (hover_condition)(d));
+		self.update_capturing(queue, hover_condition, interact_condition, |_| true);
+	}
+	/// Only inputs that pass `capture_condition` when they start interacting get captured, the rest are left for other handlers
+	pub fn update_capturing<B: InputBackend>(
+		&mut self,
+		queue: &InputQueue<B>,
+		hover_condition: impl Fn(&InputData) -> bool,
+		interact_condition: impl Fn(&InputData) -> bool,
+		capture_condition: impl Fn(&InputData) -> bool,
+	) {
+		let input = queue.input_data();
+		let hover_action = input.iter().filter(|d| (hover_condition)(d));
 		self.interact_condition.update(queue, &interact_condition);
 
 		// initial capture when just started interacting and valid
@@ -28,6 +38,7 @@ impl MultiAction {
 			.filter(|i| self.hover.current.contains(*i))
 			// but not if it started hovering at the same time (this means it just got "focus")
 			.filter(|i| !self.hover.added.contains(*i))
+			.filter(|i| (capture_condition)(i))
 		{
 			queue.start_capture(input);
 		}
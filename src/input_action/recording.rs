@@ -0,0 +1,158 @@
+use super::{InputBackend, InputEvent};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::VecDeque,
+	fs::File,
+	io::{BufReader, BufWriter},
+	path::Path,
+	time::Instant,
+};
+
+/// All the events an [`InputQueue`](super::InputQueue) received in a single `handle_events` call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedInputBatch {
+	/// Seconds since the recording started
+	pub time: f32,
+	/// Input methods are replaced with their IDs
+	pub events: Vec<InputEvent<u64>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InputRecording {
+	pub batches: Vec<RecordedInputBatch>,
+}
+impl InputRecording {
+	pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+		let file = BufWriter::new(File::create(path)?);
+		serde_json::to_writer(file, self)?;
+		Ok(())
+	}
+	pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+		let file = BufReader::new(File::open(path)?);
+		Ok(serde_json::from_reader(file)?)
+	}
+}
+
+pub(crate) struct InputRecorder {
+	start: Instant,
+	recording: InputRecording,
+}
+impl InputRecorder {
+	pub(crate) fn new() -> Self {
+		InputRecorder {
+			start: Instant::now(),
+			recording: InputRecording::default(),
+		}
+	}
+	pub(crate) fn record<B: InputBackend>(&mut self, events: &[InputEvent<B::Method>]) {
+		if events.is_empty() {
+			return;
+		}
+		self.recording.batches.push(RecordedInputBatch {
+			time: self.start.elapsed().as_secs_f32(),
+			events: events
+				.iter()
+				.map(|event| match event {
+					InputEvent::Sent { method, data } => InputEvent::Sent {
+						method: B::method_id(method),
+						data: data.clone(),
+					},
+					InputEvent::Updated { data } => InputEvent::Updated { data: data.clone() },
+					InputEvent::Left { method } => InputEvent::Left { method: *method },
+				})
+				.collect(),
+		});
+	}
+	pub(crate) fn finish(self) -> InputRecording {
+		self.recording
+	}
+}
+
+/// Plays back an [`InputRecording`] one batch at a time.
+///
+/// Either hand it to [`InputQueue::start_replay`](super::InputQueue::start_replay) to drive an existing widget,
+/// or use it as the backend of an [`InputQueue`](super::InputQueue) directly and call [`InputReplay::advance`] before each `handle_events`.
+/// Capture requests are ignored, the recorded `captured` flags are replayed instead.
+#[derive(Debug, Clone)]
+pub struct InputReplay {
+	batches: VecDeque<RecordedInputBatch>,
+	pending: VecDeque<InputEvent<u64>>,
+}
+impl InputReplay {
+	pub fn new(recording: InputRecording) -> Self {
+		InputReplay {
+			batches: recording.batches.into(),
+			pending: VecDeque::new(),
+		}
+	}
+	/// Queue up the events from the next batch, returns false once the recording is over.
+	pub fn advance(&mut self) -> bool {
+		let Some(batch) = self.batches.pop_front() else {
+			return false;
+		};
+		self.pending.extend(batch.events);
+		true
+	}
+	pub fn finished(&self) -> bool {
+		self.batches.is_empty() && self.pending.is_empty()
+	}
+}
+impl InputBackend for InputReplay {
+	type Method = u64;
+
+	fn recv_event(&mut self) -> Option<InputEvent<Self::Method>> {
+		self.pending.pop_front()
+	}
+	fn method_id(method: &Self::Method) -> u64 {
+		*method
+	}
+	fn try_capture(&self, _method: &Self::Method) {}
+	fn release(&self, _method: &Self::Method) {}
+}
+
+#[test]
+fn replay_matches_recording() {
+	use super::{
+		InputQueue,
+		mock::{MockDatamap, MockInputHandler, mock_hand, mock_input_data},
+	};
+
+	let hand = |distance| {
+		mock_input_data(
+			1,
+			mock_hand([0.0; 3]),
+			distance,
+			MockDatamap::default(),
+			false,
+		)
+	};
+	let mut queue = InputQueue::new(MockInputHandler::default());
+	queue.start_recording();
+	queue.backend_mut().send(3, hand(0.1));
+	queue.handle_events();
+	queue.backend_mut().update(hand(0.2));
+	queue.handle_events();
+	// empty batches aren't recorded
+	queue.handle_events();
+	let recording = queue.stop_recording().unwrap();
+	assert_eq!(recording.batches.len(), 2);
+
+	let mut replay = InputQueue::new(InputReplay::new(recording.clone()));
+	assert!(replay.backend_mut().advance());
+	assert!(replay.handle_events());
+	assert_eq!(replay.input_data().iter().next().unwrap().distance, 0.1);
+	assert!(replay.backend_mut().advance());
+	assert!(replay.handle_events());
+	assert_eq!(replay.input_data().iter().next().unwrap().distance, 0.2);
+	assert!(!replay.backend_mut().advance());
+
+	// replaying on top of a live queue hides the live input until the replay is over
+	queue.backend_mut().leave(3);
+	queue.start_replay(recording);
+	assert!(queue.handle_events());
+	assert_eq!(queue.input_data().iter().next().unwrap().distance, 0.1);
+	assert!(queue.handle_events());
+	assert!(queue.handle_events());
+	assert!(!queue.replaying());
+	assert!(queue.input_data().is_empty());
+}
@@ -1,4 +1,4 @@
-use super::{DeltaSet, InputQueue};
+use super::{DeltaSet, InputBackend, InputQueue};
 use rustc_hash::FxHashSet;
 use stardust_xr_fusion::input::InputData;
 use std::sync::Arc;
@@ -6,15 +6,17 @@ use std::sync::Arc;
 #[derive(Default, Debug)]
 pub struct SimpleAction(DeltaSet<Arc<InputData>>);
 impl SimpleAction {
-	pub fn update(&mut self, queue: &InputQueue, active_condition: &impl Fn(&InputData) -> bool) {
+	pub fn update<B: InputBackend>(
+		&mut self,
+		queue: &InputQueue<B>,
+		active_condition: &impl Fn(&InputData) -> bool,
+	) {
 		self.0.push_new(
 			queue
-				.input()
+				.input_data()
 				.iter()
 				// filter out every input method that doesn't meet the active condition
-				.filter(|(d, _)| (active_condition)(d))
-				// we don't need the input method anymore
-				.map(|(d, _)| d)
+				.filter(|d| (active_condition)(d))
 				.cloned(),
 		);
 	}
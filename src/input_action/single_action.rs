@@ -1,4 +1,4 @@
-use super::{DeltaSet, InputQueue, MultiAction};
+use super::{DeltaSet, InputBackend, InputQueue, MultiAction};
 use stardust_xr_fusion::input::InputData;
 use std::sync::Arc;
 
@@ -14,16 +14,30 @@ pub struct SingleAction {
 	actor: Option<Arc<InputData>>,
 }
 impl SingleAction {
-	pub fn update(
+	pub fn update<B: InputBackend>(
 		&mut self,
 		change_actor: bool,
-		queue: &InputQueue,
+		queue: &InputQueue<B>,
 		hover_condition: impl Fn(&InputData) -> bool,
 		interact_condition: impl Fn(&InputData) -> bool,
 	) {
 		self.multi
 			.update(queue, hover_condition, interact_condition);
-
+		self.update_actor(change_actor, queue);
+	}
+	/// Like [`SingleAction::update`] without changing actors, and other inputs aren't captured while there's an actor
+	pub fn update_exclusive<B: InputBackend>(
+		&mut self,
+		queue: &InputQueue<B>,
+		hover_condition: impl Fn(&InputData) -> bool,
+		interact_condition: impl Fn(&InputData) -> bool,
+	) {
+		let has_actor = self.actor.is_some();
+		self.multi
+			.update_capturing(queue, hover_condition, interact_condition, |_| !has_actor);
+		self.update_actor(false, queue);
+	}
+	fn update_actor<B: InputBackend>(&mut self, change_actor: bool, queue: &InputQueue<B>) {
 		self.actor_started = false;
 		self.actor_changed = false;
 		self.actor_stopped = false;
@@ -41,7 +55,7 @@ impl SingleAction {
 			if self.multi.interact().removed().contains(actor) {
 				self.actor_stopped = true;
 				self.actor.take();
-			} else if let Some((new_actor, _)) = queue.input().get_key_value(actor) {
+			} else if let Some(new_actor) = queue.input_data().get(actor) {
 				*actor = new_actor.clone();
 			}
 		}
@@ -52,6 +66,10 @@ impl SingleAction {
 	pub fn hovering(&self) -> &DeltaSet<Arc<InputData>> {
 		self.multi.hover()
 	}
+	/// Every captured input that's interacting, including ones that aren't the actor
+	pub fn interacting(&self) -> &DeltaSet<Arc<InputData>> {
+		self.multi.interact()
+	}
 	pub fn actor_started(&self) -> bool {
 		self.actor_started
 	}
@@ -14,7 +14,39 @@ use stardust_xr_fusion::{
 };
 use std::{marker::PhantomData, path::Path};
 
+mod sender;
+pub use sender::*;
+#[cfg(feature = "xkbcommon")]
+mod xkb;
+#[cfg(feature = "xkbcommon")]
+pub use xkb::*;
+
+/// Linux evdev keycodes, as sent through `key_state`
+pub mod keycodes {
+	pub const BACKSPACE: u32 = 14;
+	pub const TAB: u32 = 15;
+	pub const ENTER: u32 = 28;
+	pub const LEFT_CTRL: u32 = 29;
+	pub const A: u32 = 30;
+	pub const LEFT_SHIFT: u32 = 42;
+	pub const RIGHT_SHIFT: u32 = 54;
+	pub const CAPS_LOCK: u32 = 58;
+	pub const KP_ENTER: u32 = 96;
+	pub const RIGHT_CTRL: u32 = 97;
+	pub const HOME: u32 = 102;
+	pub const UP: u32 = 103;
+	pub const PAGE_UP: u32 = 104;
+	pub const LEFT: u32 = 105;
+	pub const RIGHT: u32 = 106;
+	pub const END: u32 = 107;
+	pub const DOWN: u32 = 108;
+	pub const PAGE_DOWN: u32 = 109;
+	pub const DELETE: u32 = 111;
+}
+
 pub struct KeypressInfo {
+	/// Which connection sent the key, each one has its own keymap and pressed keys
+	pub sender: UniqueName<'static>,
 	pub key: u32,
 	pub pressed: bool,
 	pub keymap_id: u64,
@@ -24,6 +56,8 @@ pub struct KeyboardHandler {
 	keymap_ids: FxHashMap<UniqueName<'static>, u64>,
 	pressed_keys: FxHashMap<UniqueName<'static>, FxHashSet<u32>>,
 	on_key: Box<dyn FnMut(KeypressInfo) + Send + Sync + 'static>,
+	/// Called after a sender's keys are released because it reset or disconnected
+	on_reset: Box<dyn FnMut(UniqueName<'static>) + Send + Sync + 'static>,
 }
 
 impl KeyboardHandler {
@@ -33,13 +67,24 @@ impl KeyboardHandler {
 		connection_point: Option<&Spatial>,
 		field: &Field,
 		handler: F,
+	) -> DbusObjectHandles {
+		Self::create_with_reset(connection, path, connection_point, field, handler, |_| {})
+	}
+	fn create_with_reset(
+		connection: Connection,
+		path: impl AsRef<Path>,
+		connection_point: Option<&Spatial>,
+		field: &Field,
+		on_key: impl FnMut(KeypressInfo) + Send + Sync + 'static,
+		on_reset: impl FnMut(UniqueName<'static>) + Send + Sync + 'static,
 	) -> DbusObjectHandles {
 		let path: OwnedObjectPath = path.as_ref().to_str().unwrap().try_into().unwrap();
 
 		let handler = KeyboardHandler {
 			keymap_ids: FxHashMap::default(),
 			pressed_keys: FxHashMap::default(),
-			on_key: Box::new(handler),
+			on_key: Box::new(on_key),
+			on_reset: Box::new(on_reset),
 		};
 
 		let abort_handle = tokio::spawn({
@@ -90,17 +135,17 @@ impl KeyboardHandler {
 		let Some(keymap_id) = self.keymap_ids.remove(&sender) else {
 			return;
 		};
-		let Some(keys) = self.pressed_keys.remove(&sender) else {
-			return;
-		};
+		let keys = self.pressed_keys.remove(&sender).unwrap_or_default();
 		for key in keys {
 			let key_info = KeypressInfo {
+				sender: sender.clone(),
 				key,
 				pressed: false,
 				keymap_id,
 			};
 			(self.on_key)(key_info);
 		}
+		(self.on_reset)(sender);
 	}
 }
 
@@ -133,7 +178,7 @@ impl KeyboardHandler {
 			return;
 		};
 
-		let sender_entry = self.pressed_keys.entry(sender).or_default();
+		let sender_entry = self.pressed_keys.entry(sender.clone()).or_default();
 		if pressed {
 			sender_entry.insert(key);
 		} else {
@@ -141,6 +186,7 @@ impl KeyboardHandler {
 		}
 
 		let key_info = KeypressInfo {
+			sender,
 			key,
 			pressed,
 			keymap_id,
@@ -210,7 +256,6 @@ async fn keyboard_receive() {
 #[tokio::test]
 async fn keyboard_send() {
 	use stardust_xr_fusion::objects::*;
-	use zbus::names::OwnedInterfaceName;
 
 	let client = stardust_xr_fusion::client::Client::connect().await.unwrap();
 	let async_loop = client.async_event_loop();
@@ -218,26 +263,15 @@ async fn keyboard_send() {
 	let connection = connect_client().await.unwrap();
 	let object_registry = object_registry::ObjectRegistry::new(&connection).await;
 
-	let objects =
-		object_registry.get_objects(&OwnedInterfaceName::try_from("org.stardustxr.XKBv1").unwrap());
-	dbg!(&objects);
-	let mut join_set = tokio::task::JoinSet::new();
-	for object in objects {
-		if object.object_path.as_str().ends_with("/keyboard_test") {
-			let connection = connection.clone();
-			join_set.spawn(async move {
-				let keyboard_handler = object
-					.to_typed_proxy::<KeyboardHandlerProxy>(&connection)
-					.await
-					.unwrap();
-				keyboard_handler.keymap(20).await.unwrap();
-				keyboard_handler.key_state(10, true).await.unwrap();
-			});
-		}
-	}
-	while let Some(result) = join_set.join_next().await {
-		result.unwrap();
-	}
+	let target = KeyboardSender::targets(&object_registry)
+		.into_iter()
+		.find(|object| object.object_path.as_str().ends_with("/keyboard_test"))
+		.expect("no keyboard_test handler to send to");
+	let mut sender = KeyboardSender::new(connection, 20);
+	sender.focus(target).await.unwrap();
+	sender.key(10, true).await.unwrap();
+	// releases the key
+	sender.close().await.unwrap();
 
 	async_loop.stop().await.unwrap();
 }
@@ -0,0 +1,197 @@
+use super::KeyboardHandlerProxy;
+use rustc_hash::FxHashSet;
+use stardust_xr_fusion::{
+	ClientHandle,
+	fields::{FieldRef, FieldRefAspect},
+	node::{NodeResult, NodeType},
+	objects::{
+		ObjectInfo,
+		object_registry::ObjectRegistry,
+		zbus::{self, Connection, names::OwnedInterfaceName},
+	},
+	query::{QueryContext, Queryable},
+	query_impl::ClientQueryContext,
+	root::RootAspect,
+	spatial::SpatialRef,
+};
+use std::sync::Arc;
+use tracing::warn;
+
+const XKB_INTERFACE: &str = "org.stardustxr.XKBv1";
+
+/// Sends keys to a [`super::KeyboardHandler`] through `org.stardustxr.XKBv1`.
+///
+/// Whatever's still held gets released with `reset` when switching targets or closing the sender.
+/// Dropping it only resets on a best-effort basis, see [`KeyboardSender::close`].
+pub struct KeyboardSender {
+	connection: Connection,
+	keymap_id: u64,
+	target: Option<(ObjectInfo, KeyboardHandlerProxy<'static>)>,
+	pressed: FxHashSet<u32>,
+}
+impl KeyboardSender {
+	/// `keymap_id` comes from registering a keymap with the server
+	pub fn new(connection: Connection, keymap_id: u64) -> Self {
+		KeyboardSender {
+			connection,
+			keymap_id,
+			target: None,
+			pressed: FxHashSet::default(),
+		}
+	}
+	/// Register `keymap` (in the XKB text format) with the server and send keys with it
+	pub async fn with_keymap(
+		connection: Connection,
+		root: &impl RootAspect,
+		keymap: &str,
+	) -> NodeResult<Self> {
+		let keymap_id = root.register_keymap(keymap.to_string()).await?;
+		Ok(Self::new(connection, keymap_id))
+	}
+	pub fn keymap_id(&self) -> u64 {
+		self.keymap_id
+	}
+
+	/// Every object that can receive keys
+	pub fn targets(registry: &ObjectRegistry) -> Vec<ObjectInfo> {
+		registry.get_objects(&OwnedInterfaceName::try_from(XKB_INTERFACE).unwrap())
+	}
+	/// The target with the closest field to `origin`, if any are within `max_distance`
+	pub async fn nearest_target(
+		&self,
+		registry: &ObjectRegistry,
+		origin: &SpatialRef,
+		max_distance: f32,
+	) -> Option<ObjectInfo> {
+		let ctx = Arc::new(SenderContext(origin.client().clone()));
+		let mut nearest: Option<(ObjectInfo, f32)> = None;
+		for object in Self::targets(registry) {
+			// keyboard handlers always have a field on the same path
+			let Some(field) = FieldRef::try_new(&self.connection, &ctx, &object, &|_| true).await
+			else {
+				continue;
+			};
+			let Ok(distance) = field.distance(origin, [0.0; 3]).await else {
+				continue;
+			};
+			if distance <= max_distance
+				&& nearest
+					.as_ref()
+					.is_none_or(|(_, nearest_distance)| distance < *nearest_distance)
+			{
+				nearest = Some((object, distance));
+			}
+		}
+		nearest.map(|(object, _)| object)
+	}
+
+	pub fn target(&self) -> Option<&ObjectInfo> {
+		self.target.as_ref().map(|(object, _)| object)
+	}
+	/// Send keys to `object` from now on, releasing everything held on the old target
+	pub async fn focus(&mut self, object: ObjectInfo) -> zbus::Result<()> {
+		if self.target().is_some_and(|target| {
+			target.bus_name == object.bus_name && target.object_path == object.object_path
+		}) {
+			return Ok(());
+		}
+		self.unfocus().await?;
+		let proxy = object
+			.to_typed_proxy::<KeyboardHandlerProxy>(&self.connection)
+			.await?;
+		proxy.keymap(self.keymap_id).await?;
+		self.target = Some((object, proxy));
+		Ok(())
+	}
+	/// Focus the target nearest to `origin`, false if there wasn't one in range
+	pub async fn focus_nearest(
+		&mut self,
+		registry: &ObjectRegistry,
+		origin: &SpatialRef,
+		max_distance: f32,
+	) -> zbus::Result<bool> {
+		let Some(object) = self.nearest_target(registry, origin, max_distance).await else {
+			return Ok(false);
+		};
+		self.focus(object).await?;
+		Ok(true)
+	}
+	pub async fn unfocus(&mut self) -> zbus::Result<()> {
+		self.pressed.clear();
+		let Some((_, proxy)) = self.target.take() else {
+			return Ok(());
+		};
+		proxy.reset().await
+	}
+
+	/// Use a different keymap, the target is told straight away
+	pub async fn set_keymap(&mut self, keymap_id: u64) -> zbus::Result<()> {
+		self.keymap_id = keymap_id;
+		if let Some((_, proxy)) = &self.target {
+			proxy.reset().await?;
+			proxy.keymap(keymap_id).await?;
+		}
+		self.pressed.clear();
+		Ok(())
+	}
+
+	/// Press or release an evdev keycode, does nothing without a target
+	pub async fn key(&mut self, key: u32, pressed: bool) -> zbus::Result<()> {
+		let Some((_, proxy)) = &self.target else {
+			return Ok(());
+		};
+		proxy.key_state(key, pressed).await?;
+		if pressed {
+			self.pressed.insert(key);
+		} else {
+			self.pressed.remove(&key);
+		}
+		Ok(())
+	}
+	/// Press and release a key
+	pub async fn tap(&mut self, key: u32) -> zbus::Result<()> {
+		self.key(key, true).await?;
+		self.key(key, false).await
+	}
+	/// Keys currently held down on the target
+	pub fn pressed(&self) -> &FxHashSet<u32> {
+		&self.pressed
+	}
+	/// Release every key on the target
+	pub async fn reset(&mut self) -> zbus::Result<()> {
+		self.pressed.clear();
+		let Some((_, proxy)) = &self.target else {
+			return Ok(());
+		};
+		proxy.reset().await
+	}
+	/// Release every key on the target and stop sending to it, waiting for the target to get the reset
+	pub async fn close(mut self) -> zbus::Result<()> {
+		self.unfocus().await
+	}
+}
+/// Resets the target from a spawned task, so the reset can be lost if the runtime shuts down first.
+/// Use [`KeyboardSender::close`] to be sure keys get released.
+impl Drop for KeyboardSender {
+	fn drop(&mut self) {
+		let Some((_, proxy)) = self.target.take() else {
+			return;
+		};
+		let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+			warn!("keyboard sender dropped outside of a tokio runtime, keys may stay held");
+			return;
+		};
+		runtime.spawn(async move {
+			let _ = proxy.reset().await;
+		});
+	}
+}
+
+/// Same client query context as [`crate::zone::Zone`], just without a zone
+struct SenderContext(Arc<ClientHandle>);
+impl QueryContext for SenderContext {}
+impl ClientQueryContext for SenderContext {
+	fn get_client_handle(self: &Arc<Self>) -> &Arc<ClientHandle> {
+		&self.0
+	}
+}
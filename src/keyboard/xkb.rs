@@ -0,0 +1,215 @@
+use super::{KeyboardHandler, KeypressInfo};
+use crate::dbus::{AbortOnDrop, DbusObjectHandles};
+use rustc_hash::{FxHashMap, FxHashSet};
+use stardust_xr_fusion::{
+	fields::Field,
+	node::NodeType,
+	objects::zbus::{Connection, names::UniqueName},
+	root::RootAspect,
+	spatial::Spatial,
+};
+use std::path::Path;
+use tokio::sync::mpsc;
+use tracing::warn;
+use xkbcommon::xkb;
+
+/// Which modifiers and locks were active for a sender when the key was pressed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ModifierState {
+	pub shift: bool,
+	pub ctrl: bool,
+	pub alt: bool,
+	pub logo: bool,
+	pub caps_lock: bool,
+	pub num_lock: bool,
+}
+impl ModifierState {
+	fn from_state(state: &xkb::State) -> Self {
+		let active = |name| state.mod_name_is_active(name, xkb::STATE_MODS_EFFECTIVE);
+		ModifierState {
+			shift: active(xkb::MOD_NAME_SHIFT),
+			ctrl: active(xkb::MOD_NAME_CTRL),
+			alt: active(xkb::MOD_NAME_ALT),
+			logo: active(xkb::MOD_NAME_LOGO),
+			caps_lock: active(xkb::MOD_NAME_CAPS),
+			num_lock: active(xkb::MOD_NAME_NUM),
+		}
+	}
+}
+
+/// What a keypress means in its sender's keymap.
+#[derive(Debug, Clone)]
+pub struct XkbKey {
+	pub keysym: xkb::Keysym,
+	/// Empty for keys that don't type anything, like modifiers and arrows
+	pub text: String,
+	pub modifiers: ModifierState,
+}
+
+/// Compiled keymaps by ID, plus each sender's modifier and lock state.
+pub struct XkbKeymaps {
+	context: xkb::Context,
+	keymaps: FxHashMap<u64, xkb::Keymap>,
+	states: FxHashMap<UniqueName<'static>, (u64, xkb::State)>,
+}
+impl Default for XkbKeymaps {
+	fn default() -> Self {
+		XkbKeymaps {
+			context: xkb::Context::new(xkb::CONTEXT_NO_FLAGS),
+			keymaps: FxHashMap::default(),
+			states: FxHashMap::default(),
+		}
+	}
+}
+impl XkbKeymaps {
+	pub fn has_keymap(&self, keymap_id: u64) -> bool {
+		self.keymaps.contains_key(&keymap_id)
+	}
+	/// Compile a keymap in the XKB text format, false if it couldn't be compiled
+	pub fn add_keymap(&mut self, keymap_id: u64, keymap: &str) -> bool {
+		let Some(keymap) = xkb::Keymap::new_from_string(
+			&self.context,
+			keymap.to_string(),
+			xkb::KEYMAP_FORMAT_TEXT_V1,
+			xkb::KEYMAP_COMPILE_NO_FLAGS,
+		) else {
+			return false;
+		};
+		self.keymaps.insert(keymap_id, keymap);
+		true
+	}
+	/// Update the sender's state and look up the key, None if its keymap isn't known yet
+	pub fn process(&mut self, key_info: &KeypressInfo) -> Option<XkbKey> {
+		let keymap = self.keymaps.get(&key_info.keymap_id)?;
+		let (keymap_id, state) = self
+			.states
+			.entry(key_info.sender.clone())
+			.or_insert_with(|| (key_info.keymap_id, xkb::State::new(keymap)));
+		// switching keymaps starts from a clean state
+		if *keymap_id != key_info.keymap_id {
+			*keymap_id = key_info.keymap_id;
+			*state = xkb::State::new(keymap);
+		}
+
+		// XKB keycodes are evdev keycodes offset by 8
+		let keycode = xkb::Keycode::new(key_info.key + 8);
+		// look the key up before it changes the state, so shift+a is still "A"
+		let key = XkbKey {
+			keysym: state.key_get_one_sym(keycode),
+			text: if key_info.pressed {
+				state.key_get_utf8(keycode)
+			} else {
+				String::new()
+			},
+			modifiers: ModifierState::from_state(state),
+		};
+		state.update_key(
+			keycode,
+			if key_info.pressed {
+				xkb::KeyDirection::Down
+			} else {
+				xkb::KeyDirection::Up
+			},
+		);
+		Some(key)
+	}
+
+	pub fn modifiers(&self, sender: &UniqueName) -> ModifierState {
+		self.states
+			.get(sender)
+			.map(|(_, state)| ModifierState::from_state(state))
+			.unwrap_or_default()
+	}
+	/// Drop a sender's modifier and lock state
+	pub fn forget_sender(&mut self, sender: &UniqueName) {
+		self.states.remove(sender);
+	}
+}
+
+/// Get a keymap's XKB text from the server.
+///
+/// Kept separate from [`XkbKeymaps`] since the xkb objects can't be held across an await.
+pub async fn fetch_keymap(root: &impl RootAspect, keymap_id: u64) -> Option<String> {
+	root.get_keymap(keymap_id)
+		.await
+		.inspect_err(|e| warn!(keymap_id, ?e, "couldn't get keymap"))
+		.ok()
+}
+
+enum XkbMessage {
+	Keymap(u64, String),
+	Key(KeypressInfo),
+	Reset(UniqueName<'static>),
+}
+
+impl KeyboardHandler {
+	/// Like [`KeyboardHandler::create`], but keymaps get fetched from the server so every key comes with its keysym and text.
+	///
+	/// The [`XkbKey`] is None when the sender's keymap couldn't be resolved.
+	/// The xkb state isn't `Send`, so it lives on its own thread and `handler` gets called from there.
+	pub fn create_xkb<F: FnMut(KeypressInfo, Option<XkbKey>) + Send + 'static>(
+		connection: Connection,
+		path: impl AsRef<Path>,
+		connection_point: Option<&Spatial>,
+		field: &Field,
+		mut handler: F,
+	) -> DbusObjectHandles {
+		let (message_tx, mut message_rx) = mpsc::unbounded_channel();
+		let keyboard_handler = KeyboardHandler::create_with_reset(
+			connection,
+			path,
+			connection_point,
+			field,
+			{
+				let message_tx = message_tx.clone();
+				move |key_info| {
+					let _ = message_tx.send(XkbMessage::Key(key_info));
+				}
+			},
+			move |sender| {
+				let _ = message_tx.send(XkbMessage::Reset(sender));
+			},
+		);
+
+		let (xkb_tx, xkb_rx) = std::sync::mpsc::channel();
+		std::thread::spawn(move || {
+			let mut keymaps = XkbKeymaps::default();
+			for message in xkb_rx {
+				match message {
+					XkbMessage::Keymap(keymap_id, keymap) => {
+						if !keymaps.add_keymap(keymap_id, &keymap) {
+							warn!(keymap_id, "couldn't compile keymap");
+						}
+					}
+					XkbMessage::Key(key_info) => {
+						let key = keymaps.process(&key_info);
+						handler(key_info, key);
+					}
+					XkbMessage::Reset(sender) => keymaps.forget_sender(&sender),
+				}
+			}
+		});
+
+		// only the keymap text crosses the await, the thread stops once this drops `xkb_tx`
+		let root = field.client().get_root().clone();
+		let abort_handle = tokio::spawn(async move {
+			let mut fetched = FxHashSet::default();
+			while let Some(message) = message_rx.recv().await {
+				// failed fetches get retried on the next key
+				if let XkbMessage::Key(key_info) = &message
+					&& !fetched.contains(&key_info.keymap_id)
+					&& let Some(keymap) = fetch_keymap(&root, key_info.keymap_id).await
+				{
+					fetched.insert(key_info.keymap_id);
+					let _ = xkb_tx.send(XkbMessage::Keymap(key_info.keymap_id, keymap));
+				}
+				if xkb_tx.send(message).is_err() {
+					break;
+				}
+			}
+		})
+		.abort_handle();
+
+		DbusObjectHandles(Box::new((keyboard_handler, AbortOnDrop(abort_handle))))
+	}
+}
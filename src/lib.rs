@@ -1,9 +1,11 @@
 pub mod accent_color;
 pub mod beam;
 pub mod button;
+pub mod confirm_button;
 pub mod dbus;
 pub mod debuggable;
 mod derezzable;
+pub mod dial;
 pub mod drop_handlers;
 mod exposure;
 mod grabbable;
@@ -13,8 +15,14 @@ pub mod keyboard;
 pub mod lines;
 pub mod mouse;
 pub mod multi;
+/// Undo history and saved state helpers shared by grabbables and reparentables
+mod persistence;
 pub mod reparentable;
+pub mod scroll_view;
+pub mod slider;
 pub mod state_machine;
+pub mod text_field;
+pub mod toggle;
 pub mod touch_plane;
 pub mod tracked;
 pub mod zone;
@@ -51,6 +59,22 @@ pub trait VisualDebug {
 pub trait UIElement {
 	/// Handle events, returns true if events were handled (e.g. when input has been updated)
 	fn handle_events(&mut self) -> bool;
+	/// Handle events until they've actually been handled, so this can be driven from a task instead of every frame.
+	///
+	/// `wait` should resolve when new events might have arrived, such as the client's async event handle.
+	fn wait_for_update<F: Future<Output = ()>>(
+		&mut self,
+		mut wait: impl FnMut() -> F,
+	) -> impl Future<Output = ()>
+	where
+		Self: Sized,
+	{
+		async move {
+			while !self.handle_events() {
+				wait().await;
+			}
+		}
+	}
 }
 pub trait FrameSensitive {
 	fn frame(&mut self, info: &FrameInfo);
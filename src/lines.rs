@@ -315,6 +315,14 @@ pub fn arc(segments: usize, start_angle: f32, end_angle: f32, radius: f32) -> Li
 	}
 }
 
+/// on the XZ plane, like [`arc`] but with `segments + 1` points so it ends exactly at `end_angle`
+pub fn arc_inclusive(segments: usize, start_angle: f32, end_angle: f32, radius: f32) -> Line {
+	let mut line = arc(segments, start_angle, end_angle, radius);
+	line.points
+		.extend(arc(1, end_angle, end_angle, radius).points);
+	line
+}
+
 pub fn line_from_points(points: Vec<impl Into<Vector3<f32>>>) -> Line {
 	Line {
 		points: points
@@ -0,0 +1,56 @@
+use glam::{Quat, Vec4};
+use std::collections::VecDeque;
+
+/// States something came to rest in, so changes can be undone.
+#[derive(Debug)]
+pub(crate) struct History<T> {
+	undo: VecDeque<T>,
+	redo: Vec<T>,
+	current: T,
+}
+impl<T: Clone + PartialEq> History<T> {
+	pub(crate) fn new(current: T) -> Self {
+		History {
+			undo: VecDeque::new(),
+			redo: Vec::new(),
+			current,
+		}
+	}
+	/// Record a new resting state, keeping at most `limit` states to undo to
+	pub(crate) fn commit(&mut self, entry: T, limit: usize) {
+		if entry == self.current {
+			return;
+		}
+		self.undo
+			.push_back(std::mem::replace(&mut self.current, entry));
+		while self.undo.len() > limit {
+			self.undo.pop_front();
+		}
+		self.redo.clear();
+	}
+	pub(crate) fn undo(&mut self) -> Option<T> {
+		let entry = self.undo.pop_back()?;
+		self.redo
+			.push(std::mem::replace(&mut self.current, entry.clone()));
+		Some(entry)
+	}
+	pub(crate) fn redo(&mut self) -> Option<T> {
+		let entry = self.redo.pop()?;
+		self.undo
+			.push_back(std::mem::replace(&mut self.current, entry.clone()));
+		Some(entry)
+	}
+}
+
+/// Saved rotations can be hand edited or zeroed, so fall back to no rotation
+pub(crate) fn rotation_from_array(rotation: [f32; 4]) -> Quat {
+	Vec4::from_array(rotation)
+		.try_normalize()
+		.map_or(Quat::IDENTITY, Quat::from_vec4)
+}
+
+#[test]
+fn invalid_saved_rotation() {
+	assert_eq!(rotation_from_array([0.0; 4]), Quat::IDENTITY);
+	assert_eq!(rotation_from_array([f32::NAN; 4]), Quat::IDENTITY);
+}
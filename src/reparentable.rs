@@ -1,5 +1,10 @@
-use crate::dbus::{AbortOnDrop, DbusObjectHandle, DbusObjectHandles};
+use crate::{
+	dbus::{AbortOnDrop, DbusObjectHandle, DbusObjectHandles},
+	persistence::{History, rotation_from_array},
+};
 use futures_util::StreamExt;
+use glam::{Quat, Vec3};
+use serde::{Deserialize, Serialize};
 use stardust_xr_fusion::{
 	fields::Field,
 	node::{NodeResult, NodeType},
@@ -23,15 +28,104 @@ use std::{
 };
 use tokio::sync::watch;
 
+/// The client a [`Reparentable`] is parented to and the spatial it parented it to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ReparentTarget {
+	/// Unique bus name of the client that reparented it
+	pub client: String,
+	/// ID of the spatial as that client exported it
+	pub spatial: u64,
+}
+
+/// Transform of a [`Reparentable`] relative to its original parent and what it's parented to, to save with the client's state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReparentableState {
+	pub translation: [f32; 3],
+	pub rotation: [f32; 4],
+	pub scale: [f32; 3],
+	/// Only restored if that client still has the spatial exported
+	#[serde(default)]
+	pub parent: Option<ReparentTarget>,
+}
+
 pub struct Reparentable {
 	pub spatial: SpatialRef,
 	_object_handles: DbusObjectHandles,
 	transform_changed: Arc<Mutex<Option<Transform>>>,
-	reparented: Arc<AtomicBool>,
+	parent: ParentState,
 }
 impl Reparentable {
 	pub fn reparented(&self) -> bool {
-		self.reparented.load(Ordering::Relaxed)
+		self.parent.reparented.load(Ordering::Relaxed)
+	}
+	/// Bus name of the client this is reparented to, if it's known
+	pub fn parented_to(&self) -> Option<UniqueName<'static>> {
+		self.parent
+			.target()
+			.and_then(|target| UniqueName::try_from(target.client).ok())
+	}
+	pub fn parent_target(&self) -> Option<ReparentTarget> {
+		self.parent.target()
+	}
+	/// Parent it to `target`, or back to its original parent for None, keeping it where it is.
+	///
+	/// The client that owns the target isn't told about it. Doesn't get recorded in the history.
+	pub fn set_parent(
+		&self,
+		target: Option<ReparentTarget>,
+	) -> impl Future<Output = NodeResult<()>> + Send + 'static {
+		self.parent.clone().apply(target)
+	}
+
+	/// How many reparents can be undone, 0 (the default) turns the history off
+	pub fn set_history_limit(&self, limit: usize) {
+		self.parent.history.lock().unwrap().limit = limit;
+	}
+	/// Go back to what it was parented to before the last reparent, false if there's nothing to undo
+	pub async fn undo(&self) -> NodeResult<bool> {
+		let Some(target) = self.parent.history.lock().unwrap().history.undo() else {
+			return Ok(false);
+		};
+		self.set_parent(target).await?;
+		Ok(true)
+	}
+	pub async fn redo(&self) -> NodeResult<bool> {
+		let Some(target) = self.parent.history.lock().unwrap().history.redo() else {
+			return Ok(false);
+		};
+		self.set_parent(target).await?;
+		Ok(true)
+	}
+	/// Where it is relative to its original parent, even while reparented
+	pub async fn save_state(&self) -> NodeResult<ReparentableState> {
+		let transform = self
+			.spatial
+			.get_transform(&self.parent.initial_parent)
+			.await?;
+		Ok(ReparentableState {
+			translation: transform.translation.map_or(Vec3::ZERO, Vec3::from).into(),
+			rotation: transform
+				.rotation
+				.map_or(Quat::IDENTITY, Quat::from)
+				.to_array(),
+			scale: transform.scale.map_or(Vec3::ONE, Vec3::from).into(),
+			parent: self.parent_target(),
+		})
+	}
+	/// Put it back where it was relative to its original parent, then back into its saved parent
+	pub async fn restore_state(&self, state: &ReparentableState) -> NodeResult<()> {
+		self.parent.spatial.set_relative_transform(
+			&self.parent.initial_parent,
+			Transform::from_translation_rotation_scale(
+				state.translation,
+				rotation_from_array(state.rotation),
+				state.scale,
+			),
+		)?;
+		if state.parent.is_some() {
+			self.set_parent(state.parent.clone()).await?;
+		}
+		Ok(())
 	}
 	pub fn transform_recv(&self) -> ReparentTransformReceiver {
 		ReparentTransformReceiver(self.transform_changed.clone())
@@ -49,18 +143,24 @@ impl Reparentable {
 
 		let (captured_by_sender, captured_by) = watch::channel(None);
 		let transform_changed = Arc::new(Mutex::new(None));
-		let reparented = Arc::new(AtomicBool::new(false));
-		let reparentable = ReparentableInner {
+		let parent = ParentState {
 			initial_parent: initial_parent.clone(),
 			spatial: spatial.clone(),
+			target: Arc::new(Mutex::new(None)),
+			reparented: Arc::new(AtomicBool::new(false)),
+			history: Arc::new(Mutex::new(ParentHistory {
+				history: History::new(None),
+				limit: 0,
+			})),
+		};
+		let reparentable = ReparentableInner {
 			captured_by,
-			parented_to: None,
+			parent: parent.clone(),
 			transform_changed: transform_changed.clone(),
-			reparented: reparented.clone(),
 		};
 		let reparent_lock = ReparentLock {
 			watch: captured_by_sender,
-			initial_parent,
+			initial_parent: initial_parent.clone(),
 			spatial: spatial.clone().as_spatial_ref(),
 			lock_transform: None,
 		};
@@ -134,7 +234,7 @@ impl Reparentable {
 		.abort_handle();
 
 		Ok(Reparentable {
-			spatial: spatial.as_spatial_ref(),
+			spatial: spatial.clone().as_spatial_ref(),
 			transform_changed,
 			_object_handles: DbusObjectHandles(Box::new((
 				AbortOnDrop(abort_handle),
@@ -147,40 +247,103 @@ impl Reparentable {
 				),
 				DbusObjectHandle::<ReparentLock>(connection.clone(), path.clone(), PhantomData),
 			))),
-			reparented,
+			parent,
 		})
 	}
+	/// Create a reparentable where `state` was saved, e.g. from the client's saved state
+	pub async fn create_with_state(
+		connection: Connection,
+		path: impl AsRef<Path>,
+		initial_parent: SpatialRef,
+		spatial: Spatial,
+		field: Option<Field>,
+		state: &ReparentableState,
+	) -> NodeResult<Self> {
+		let reparentable = Self::create(connection, path, initial_parent, spatial, field)?;
+		reparentable.restore_state(state).await?;
+		Ok(reparentable)
+	}
 }
 
-struct ReparentableInner {
+struct ParentHistory {
+	history: History<Option<ReparentTarget>>,
+	limit: usize,
+}
+/// What it's parented to, shared between the handle and the DBus interface
+#[derive(Clone)]
+struct ParentState {
 	initial_parent: SpatialRef,
 	spatial: Spatial,
+	target: Arc<Mutex<Option<ReparentTarget>>>,
+	reparented: Arc<AtomicBool>,
+	history: Arc<Mutex<ParentHistory>>,
+}
+impl ParentState {
+	fn target(&self) -> Option<ReparentTarget> {
+		self.target.lock().unwrap().clone()
+	}
+	fn set_target(&self, target: Option<ReparentTarget>) {
+		self.reparented.store(target.is_some(), Ordering::Relaxed);
+		*self.target.lock().unwrap() = target;
+	}
+	/// Add the current parent to the history, if it's on
+	fn record(&self) {
+		let target = self.target();
+		let mut history = self.history.lock().unwrap();
+		let limit = history.limit;
+		if limit > 0 {
+			history.history.commit(target, limit);
+		}
+	}
+	async fn apply(self, target: Option<ReparentTarget>) -> NodeResult<()> {
+		match &target {
+			Some(target) => {
+				let parent =
+					SpatialRef::import(self.initial_parent.client(), target.spatial).await?;
+				self.spatial.set_spatial_parent_in_place(&parent)?;
+			}
+			None => self
+				.spatial
+				.set_spatial_parent_in_place(&self.initial_parent)?,
+		}
+		self.set_target(target);
+		Ok(())
+	}
+}
+
+struct ReparentableInner {
 	captured_by: watch::Receiver<Option<UniqueName<'static>>>,
-	parented_to: Option<UniqueName<'static>>,
+	parent: ParentState,
 	transform_changed: Arc<Mutex<Option<Transform>>>,
-	reparented: Arc<AtomicBool>,
 }
 impl ReparentableInner {
 	fn client_lost(&mut self, name: UniqueName<'static>, lock_transform: Option<Transform>) {
-		if self.parented_to.as_ref() == Some(&name) {
-			self.parented_to = None;
-			self.reparented.store(false, Ordering::Relaxed);
+		if self
+			.parent
+			.target()
+			.is_some_and(|target| target.client == name.as_str())
+		{
+			self.parent.set_target(None);
+			self.parent.record();
 			if let Some(transform) = lock_transform {
-				let _ = self.spatial.set_spatial_parent(&self.initial_parent);
-				let _ = self.spatial.set_local_transform(transform);
+				let _ = self
+					.parent
+					.spatial
+					.set_spatial_parent(&self.parent.initial_parent);
+				let _ = self.parent.spatial.set_local_transform(transform);
 				self.transform_changed.lock().unwrap().replace(transform);
 			} else {
 				let _ = self
 					.spatial
-					.set_spatial_parent_in_place(&self.initial_parent);
+					.set_spatial_parent_in_place(&self.parent.initial_parent);
 				self.request_relative_transform();
 			}
 		}
 	}
 	fn request_relative_transform(&self) {
 		let tx = self.transform_changed.clone();
-		let initial_parent = self.initial_parent.clone();
-		let spatial = self.spatial.clone();
+		let initial_parent = self.parent.initial_parent.clone();
+		let spatial = self.parent.spatial.clone();
 		tokio::spawn(async move {
 			let transform = spatial.get_transform(&initial_parent).await.unwrap();
 			tx.lock().unwrap().replace(transform);
@@ -191,8 +354,8 @@ impl Drop for ReparentableInner {
 	fn drop(&mut self) {
 		_ = self
 			.spatial
-			.set_spatial_parent_in_place(&self.initial_parent);
-		self.reparented.store(false, Ordering::Relaxed);
+			.set_spatial_parent_in_place(&self.parent.initial_parent);
+		self.parent.set_target(None);
 		self.request_relative_transform();
 	}
 }
@@ -205,15 +368,23 @@ impl ReparentableInner {
 		{
 			return;
 		}
-		let Ok(spatial_ref) = SpatialRef::import(self.initial_parent.client(), spatial).await
+		let Ok(spatial_ref) =
+			SpatialRef::import(self.parent.initial_parent.client(), spatial).await
 		else {
 			return;
 		};
-		if let Some(sender) = header.sender() {
-			self.parented_to = Some(sender.to_owned());
-		}
-		let _ = self.spatial.set_spatial_parent_in_place(&spatial_ref);
-		self.reparented.store(true, Ordering::Relaxed);
+		let _ = self
+			.parent
+			.spatial
+			.set_spatial_parent_in_place(&spatial_ref);
+		self.parent.set_target(Some(ReparentTarget {
+			client: header
+				.sender()
+				.map(|sender| sender.to_string())
+				.unwrap_or_default(),
+			spatial,
+		}));
+		self.parent.record();
 	}
 	async fn unparent(&mut self, #[zbus(header)] header: Header<'_>) {
 		if let Some(captured) = self.captured_by.borrow_and_update().deref()
@@ -224,10 +395,10 @@ impl ReparentableInner {
 		}
 		let _ = self
 			.spatial
-			.set_spatial_parent_in_place(&self.initial_parent);
+			.set_spatial_parent_in_place(&self.parent.initial_parent);
 		self.request_relative_transform();
-		self.parented_to.take();
-		self.reparented.store(false, Ordering::Relaxed);
+		self.parent.set_target(None);
+		self.parent.record();
 	}
 	/// Use this to reset the local transform of the zoneable object relative to an object.
 	async fn reset_local_transform(
@@ -242,7 +413,8 @@ impl ReparentableInner {
 			return;
 		}
 
-		let Ok(relative_to) = SpatialRef::import(self.initial_parent.client(), relative_to).await
+		let Ok(relative_to) =
+			SpatialRef::import(self.parent.initial_parent.client(), relative_to).await
 		else {
 			return;
 		};
@@ -0,0 +1,315 @@
+use crate::{
+	DebugSettings, FrameSensitive, MomentumSettings, UIElement, VisualDebug,
+	hover_plane::{HoverPlane, HoverPlaneSettings},
+};
+use glam::{Vec2, vec2};
+use stardust_xr_fusion::{
+	node::NodeError,
+	root::FrameInfo,
+	spatial::{Spatial, SpatialAspect, SpatialRefAspect, Transform},
+	values::Vector2,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollAxis {
+	Horizontal,
+	Vertical,
+	Both,
+}
+impl ScrollAxis {
+	fn mask(self) -> Vec2 {
+		match self {
+			ScrollAxis::Horizontal => vec2(1.0, 0.0),
+			ScrollAxis::Vertical => vec2(0.0, 1.0),
+			ScrollAxis::Both => Vec2::ONE,
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct ScrollViewSettings {
+	pub axis: ScrollAxis,
+	/// None means the content stops as soon as it's let go
+	pub momentum: Option<MomentumSettings>,
+	/// Meters per unit of `scroll_continuous`
+	pub continuous_gain: f32,
+	/// Meters per `scroll_discrete` step
+	pub discrete_gain: f32,
+	pub hover_plane: HoverPlaneSettings,
+}
+impl Default for ScrollViewSettings {
+	fn default() -> Self {
+		Self {
+			axis: ScrollAxis::Vertical,
+			momentum: Some(MomentumSettings {
+				drag: 4.0,
+				threshold: 0.05,
+			}),
+			continuous_gain: 0.001,
+			discrete_gain: 0.02,
+			hover_plane: HoverPlaneSettings::default(),
+		}
+	}
+}
+
+struct ScrollItem {
+	slot: Spatial,
+	/// Center from the content's top left, y going down
+	position: Vec2,
+	size: Vec2,
+	visible: bool,
+}
+
+/// Drag or scroll content around inside a fixed viewport.
+///
+/// Items are added with [`ScrollView::add_item`] and hidden whenever they don't fit entirely in the viewport.
+pub struct ScrollView {
+	settings: ScrollViewSettings,
+	plane: HoverPlane,
+	content: Spatial,
+	items: Vec<ScrollItem>,
+	/// How far the content is scrolled from the top left, y going down
+	offset: Vec2,
+	velocity: Vec2,
+	drag_point: Option<Vec2>,
+	last_offset: Vec2,
+	changed: bool,
+}
+impl ScrollView {
+	pub fn create(
+		parent: &impl SpatialRefAspect,
+		transform: Transform,
+		size: impl Into<Vector2<f32>>,
+		thickness: f32,
+		settings: ScrollViewSettings,
+	) -> Result<Self, NodeError> {
+		let size = size.into();
+		let plane = HoverPlane::create(
+			parent,
+			transform,
+			size,
+			thickness,
+			size.x * -0.5..size.x * 0.5,
+			size.y * 0.5..size.y * -0.5,
+			settings.hover_plane.clone(),
+		)?;
+		let content = Spatial::create(plane.root(), Transform::identity())?;
+		let mut scroll_view = ScrollView {
+			settings,
+			plane,
+			content,
+			items: Vec::new(),
+			offset: Vec2::ZERO,
+			velocity: Vec2::ZERO,
+			drag_point: None,
+			last_offset: Vec2::ZERO,
+			changed: false,
+		};
+		scroll_view.update_content();
+		Ok(scroll_view)
+	}
+
+	/// Add a slot for content `size` big centered at `position` from the content's top left, with y going down.
+	///
+	/// Parent the item's nodes to the returned spatial so they get hidden with it.
+	pub fn add_item(
+		&mut self,
+		position: impl Into<Vector2<f32>>,
+		size: impl Into<Vector2<f32>>,
+	) -> Result<Spatial, NodeError> {
+		let position: Vector2<f32> = position.into();
+		let size: Vector2<f32> = size.into();
+		let (position, size) = (Vec2::from(position), Vec2::from(size));
+		let slot = Spatial::create(
+			&self.content,
+			Transform::from_translation([position.x, -position.y, 0.0]),
+		)?;
+		self.items.push(ScrollItem {
+			slot: slot.clone(),
+			position,
+			size,
+			visible: true,
+		});
+		self.update_content();
+		Ok(slot)
+	}
+	pub fn clear_items(&mut self) {
+		self.items.clear();
+		self.offset = Vec2::ZERO;
+		self.velocity = Vec2::ZERO;
+		self.update_content();
+	}
+
+	/// Everything scrolls relative to this, items are already parented to it
+	pub fn content(&self) -> &Spatial {
+		&self.content
+	}
+	pub fn hover_plane(&self) -> &HoverPlane {
+		&self.plane
+	}
+	pub fn offset(&self) -> Vector2<f32> {
+		self.offset.into()
+	}
+	/// Clamped to the content bounds, stops any momentum
+	pub fn scroll_to(&mut self, offset: impl Into<Vector2<f32>>) {
+		let offset: Vector2<f32> = offset.into();
+		self.offset = offset.into();
+		self.velocity = Vec2::ZERO;
+		self.update_content();
+	}
+	/// Whether the offset, dragging or which items are visible changed during the last update
+	pub fn changed(&self) -> bool {
+		self.changed
+	}
+	pub fn is_moving(&self) -> bool {
+		self.drag_point.is_some() || self.velocity != Vec2::ZERO
+	}
+
+	fn viewport_size(&self) -> Vec2 {
+		let x = &self.plane.x_range;
+		let y = &self.plane.y_range;
+		vec2((x.end - x.start).abs(), (y.end - y.start).abs())
+	}
+	fn content_size(&self) -> Vec2 {
+		self.items
+			.iter()
+			.map(|item| item.position + item.size * 0.5)
+			.fold(Vec2::ZERO, Vec2::max)
+	}
+	fn max_offset(&self) -> Vec2 {
+		(self.content_size() - self.viewport_size()).max(Vec2::ZERO) * self.settings.axis.mask()
+	}
+
+	/// Clamp the offset, move the content and hide whatever's outside the viewport
+	/// True if any item was shown or hidden
+	fn update_content(&mut self) -> bool {
+		let max_offset = self.max_offset();
+		let clamped = self.offset.clamp(Vec2::ZERO, max_offset);
+		// hitting the edge kills momentum along that axis
+		if clamped.x != self.offset.x {
+			self.velocity.x = 0.0;
+		}
+		if clamped.y != self.offset.y {
+			self.velocity.y = 0.0;
+		}
+		self.offset = clamped;
+
+		let viewport = self.viewport_size();
+		let _ = self
+			.content
+			.set_local_transform(Transform::from_translation([
+				viewport.x * -0.5 - self.offset.x,
+				viewport.y * 0.5 + self.offset.y,
+				0.0,
+			]));
+
+		let visible_min = self.offset;
+		let visible_max = self.offset + viewport;
+		let mut visibility_changed = false;
+		for item in &mut self.items {
+			let min = item.position - item.size * 0.5;
+			let max = item.position + item.size * 0.5;
+			let visible = min.cmpge(visible_min).all() && max.cmple(visible_max).all();
+			if visible != item.visible {
+				visibility_changed = true;
+				item.visible = visible;
+				let scale = if visible { 1.0 } else { 0.0 };
+				let _ = item
+					.slot
+					.set_local_transform(Transform::from_scale([scale; 3]));
+			}
+		}
+		visibility_changed
+	}
+	fn interact_point(&self) -> Option<Vec2> {
+		let actor = self.plane.interact_status().actor()?;
+		let point = HoverPlane::interact_point_local(actor);
+		// y going down like the offset
+		Some(vec2(point.x, -point.y))
+	}
+}
+impl UIElement for ScrollView {
+	fn handle_events(&mut self) -> bool {
+		let handled = self.plane.update();
+		let mask = self.settings.axis.mask();
+		let previous_offset = self.offset;
+		let was_dragging = self.drag_point.is_some();
+
+		let mut scroll = Vec2::ZERO;
+		for input in self.plane.hovering().current() {
+			scroll += input.datamap.with_data(|datamap| {
+				let continuous = datamap.idx("scroll_continuous").as_vector();
+				let discrete = datamap.idx("scroll_discrete").as_vector();
+				vec2(
+					continuous.idx(0).as_f32() * self.settings.continuous_gain
+						+ discrete.idx(0).as_f32() * self.settings.discrete_gain,
+					continuous.idx(1).as_f32() * self.settings.continuous_gain
+						+ discrete.idx(1).as_f32() * self.settings.discrete_gain,
+				)
+			});
+		}
+		if scroll != Vec2::ZERO {
+			self.offset += scroll * mask;
+			self.velocity = Vec2::ZERO;
+		}
+
+		let interact_point = self.interact_point();
+		match (self.drag_point, interact_point) {
+			(Some(last), Some(point)) => {
+				self.offset -= (point - last) * mask;
+				self.drag_point = Some(point);
+			}
+			(None, Some(point)) => {
+				self.velocity = Vec2::ZERO;
+				self.last_offset = self.offset;
+				self.drag_point = Some(point);
+			}
+			(Some(_), None) => {
+				// let go, velocity from the last frame carries on
+				self.drag_point = None;
+				let above_threshold = self.settings.momentum.is_some_and(|momentum| {
+					self.velocity.length_squared() > momentum.threshold.powi(2)
+				});
+				if !above_threshold {
+					self.velocity = Vec2::ZERO;
+				}
+			}
+			(None, None) => (),
+		}
+		let visibility_changed = self.update_content();
+		self.changed = visibility_changed
+			|| self.offset != previous_offset
+			|| self.drag_point.is_some() != was_dragging;
+		handled
+	}
+}
+impl FrameSensitive for ScrollView {
+	fn frame(&mut self, info: &FrameInfo) {
+		if info.delta <= 0.0 {
+			return;
+		}
+		if self.drag_point.is_some() {
+			self.velocity = (self.offset - self.last_offset) / info.delta;
+			self.last_offset = self.offset;
+			return;
+		}
+		let Some(momentum) = self.settings.momentum else {
+			return;
+		};
+		if self.velocity == Vec2::ZERO {
+			return;
+		}
+		self.velocity *= (1.0 - momentum.drag * info.delta).clamp(0.0, 1.0);
+		if self.velocity.length_squared() < 0.0001 {
+			self.velocity = Vec2::ZERO;
+			return;
+		}
+		self.offset += self.velocity * info.delta;
+		self.update_content();
+	}
+}
+impl VisualDebug for ScrollView {
+	fn set_debug(&mut self, settings: Option<DebugSettings>) {
+		self.plane.set_debug(settings)
+	}
+}
@@ -0,0 +1,294 @@
+use crate::{
+	UIElement, VisualDebug,
+	dbus::DbusObjectHandles,
+	input_action::Gesture,
+	keyboard::{KeyboardHandler, KeypressInfo, keycodes},
+	lines::{LineExt, circle, line_from_points},
+	touch_plane::TouchPlane,
+};
+use glam::{Mat4, vec3};
+use stardust_xr_fusion::{
+	drawable::{Lines, LinesAspect},
+	node::NodeError,
+	objects::zbus::Connection,
+	spatial::{SpatialRefAspect, Transform},
+	values::color::{Rgba, color_space::LinearRgb, rgba_linear},
+};
+use std::{f32::consts::FRAC_PI_2, path::Path};
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SliderOrientation {
+	/// Minimum on the left
+	Horizontal,
+	/// Minimum at the bottom
+	Vertical,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SliderVisualSettings {
+	pub line_thickness: f32,
+	pub track_color: Rgba<f32, LinearRgb>,
+	pub accent_color: Rgba<f32, LinearRgb>,
+}
+impl Default for SliderVisualSettings {
+	fn default() -> Self {
+		Self {
+			line_thickness: 0.005,
+			track_color: rgba_linear!(0.5, 0.5, 0.5, 1.0),
+			accent_color: rgba_linear!(0.0, 1.0, 0.75, 1.0),
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SliderSettings {
+	pub orientation: SliderOrientation,
+	pub min: f32,
+	pub max: f32,
+	/// None means the value is continuous
+	pub step: Option<f32>,
+	/// Starting value
+	pub default: f32,
+	/// Double tapping goes back to the default value
+	pub double_tap_reset: bool,
+	pub visuals: Option<SliderVisualSettings>,
+}
+impl Default for SliderSettings {
+	fn default() -> Self {
+		Self {
+			orientation: SliderOrientation::Horizontal,
+			min: 0.0,
+			max: 1.0,
+			step: None,
+			default: 0.0,
+			double_tap_reset: false,
+			visuals: Some(SliderVisualSettings::default()),
+		}
+	}
+}
+impl SliderSettings {
+	/// Clamp to the range and round to the nearest step
+	pub fn snap(&self, value: f32) -> f32 {
+		let (low, high) = (self.min.min(self.max), self.min.max(self.max));
+		let value = match self.step.filter(|step| *step > 0.0) {
+			Some(step) => self.min + ((value - self.min) / step).round() * step,
+			None => value,
+		};
+		value.clamp(low, high)
+	}
+	/// How much the arrow keys change the value
+	fn fine_step(&self) -> f32 {
+		self.step
+			.filter(|step| *step > 0.0)
+			.unwrap_or((self.max - self.min).abs() / 100.0)
+	}
+}
+
+/// Drag along it with a finger, tip or pointer to pick a value.
+///
+/// Pointers get mapped onto the slider by the [`TouchPlane`] where their ray hits it, so there's no separate [`HoverPlane`](crate::hover_plane::HoverPlane).
+/// One would need its own input handler on the same field, and the two would fight over capturing the same pointer.
+pub struct Slider {
+	settings: SliderSettings,
+	length: f32,
+	touch_plane: TouchPlane,
+	lines: Option<Lines>,
+	value: f32,
+	changed: bool,
+	keys: Option<(mpsc::UnboundedReceiver<KeypressInfo>, DbusObjectHandles)>,
+}
+impl Slider {
+	/// `length` is along the slider's orientation, `width` across it
+	pub fn create(
+		parent: &impl SpatialRefAspect,
+		transform: Transform,
+		length: f32,
+		width: f32,
+		settings: SliderSettings,
+	) -> Result<Self, NodeError> {
+		let half_length = length * 0.5;
+		let half_width = width * 0.5;
+		let touch_plane = match settings.orientation {
+			SliderOrientation::Horizontal => TouchPlane::create(
+				parent,
+				transform,
+				[length, width],
+				0.015,
+				-half_length..half_length,
+				half_width..-half_width,
+			)?,
+			SliderOrientation::Vertical => TouchPlane::create(
+				parent,
+				transform,
+				[width, length],
+				0.015,
+				-half_width..half_width,
+				half_length..-half_length,
+			)?,
+		};
+		let lines = settings
+			.visuals
+			.map(|_| Lines::create(touch_plane.root(), Transform::identity(), &[]))
+			.transpose()?;
+		let slider = Slider {
+			value: settings.snap(settings.default),
+			settings,
+			length,
+			touch_plane,
+			lines,
+			changed: false,
+			keys: None,
+		};
+		slider.update_visuals();
+		Ok(slider)
+	}
+
+	/// Let keyboards fine-adjust the value with the arrow keys, home and end
+	pub fn attach_keyboard(&mut self, connection: Connection, path: impl AsRef<Path>) {
+		let (key_tx, key_rx) = mpsc::unbounded_channel();
+		let handles = KeyboardHandler::create(
+			connection,
+			path,
+			Some(self.touch_plane.root()),
+			self.touch_plane.field(),
+			move |key_info| {
+				let _ = key_tx.send(key_info);
+			},
+		);
+		self.keys = Some((key_rx, handles));
+	}
+
+	pub fn value(&self) -> f32 {
+		self.value
+	}
+	/// Doesn't count as a change
+	pub fn set_value(&mut self, value: f32) {
+		self.value = self.settings.snap(value);
+		self.update_visuals();
+	}
+	/// The new value if it changed during the last update
+	pub fn changed(&self) -> Option<f32> {
+		self.changed.then_some(self.value)
+	}
+	pub fn touch_plane(&self) -> &TouchPlane {
+		&self.touch_plane
+	}
+
+	/// Position along the slider from -length/2 to length/2
+	fn value_to_position(&self, value: f32) -> f32 {
+		let range = self.settings.max - self.settings.min;
+		if range == 0.0 {
+			return 0.0;
+		}
+		((value - self.settings.min) / range - 0.5) * self.length
+	}
+	fn position_to_value(&self, position: f32) -> f32 {
+		let t = position / self.length + 0.5;
+		self.settings.min + t * (self.settings.max - self.settings.min)
+	}
+
+	fn set_value_changed(&mut self, value: f32) {
+		let value = self.settings.snap(value);
+		if value != self.value {
+			self.value = value;
+			self.changed = true;
+		}
+	}
+	fn handle_keys(&mut self) -> bool {
+		let Some((key_rx, _)) = &mut self.keys else {
+			return false;
+		};
+		let mut keys = Vec::new();
+		while let Ok(key_info) = key_rx.try_recv() {
+			keys.push(key_info);
+		}
+		for key_info in &keys {
+			if !key_info.pressed {
+				continue;
+			}
+			let fine_step = self.settings.fine_step();
+			let value = match key_info.key {
+				keycodes::RIGHT | keycodes::UP => self.value + fine_step,
+				keycodes::LEFT | keycodes::DOWN => self.value - fine_step,
+				keycodes::PAGE_UP => self.value + fine_step * 10.0,
+				keycodes::PAGE_DOWN => self.value - fine_step * 10.0,
+				keycodes::HOME => self.settings.min,
+				keycodes::END => self.settings.max,
+				_ => continue,
+			};
+			self.set_value_changed(value);
+		}
+		!keys.is_empty()
+	}
+
+	fn update_visuals(&self) {
+		let (Some(lines), Some(visuals)) = (&self.lines, self.settings.visuals) else {
+			return;
+		};
+		let half_length = self.length * 0.5;
+		let thumb = self.value_to_position(self.value);
+		let thumb_radius = visuals.line_thickness * 2.0;
+		// drawn horizontally, then rotated for vertical sliders
+		let orientation = match self.settings.orientation {
+			SliderOrientation::Horizontal => Mat4::IDENTITY,
+			SliderOrientation::Vertical => Mat4::from_rotation_z(FRAC_PI_2),
+		};
+		let track = [
+			line_from_points(vec![vec3(-half_length, 0.0, 0.0), vec3(thumb, 0.0, 0.0)])
+				.color(visuals.accent_color),
+			line_from_points(vec![vec3(thumb, 0.0, 0.0), vec3(half_length, 0.0, 0.0)])
+				.color(visuals.track_color),
+			circle(16, 0.0, thumb_radius)
+				.transform(
+					Mat4::from_translation(vec3(thumb, 0.0, 0.0))
+						* Mat4::from_rotation_x(FRAC_PI_2),
+				)
+				.color(visuals.accent_color),
+		];
+		let _ = lines.set_lines(
+			&track
+				.into_iter()
+				.map(|line| {
+					line.thickness(visuals.line_thickness)
+						.transform(orientation)
+				})
+				.collect::<Vec<_>>(),
+		);
+	}
+}
+impl UIElement for Slider {
+	fn handle_events(&mut self) -> bool {
+		self.changed = false;
+		let keys_handled = self.handle_keys();
+		let input_handled = self.touch_plane.handle_events();
+		if input_handled {
+			if self.settings.double_tap_reset
+				&& self
+					.touch_plane
+					.gestures()
+					.iter()
+					.any(|gesture| matches!(gesture, Gesture::DoubleTap { .. }))
+			{
+				self.set_value_changed(self.settings.default);
+			} else if let Some(input) = self.touch_plane.action().interact().current().iter().next()
+			{
+				let point = self.touch_plane.interact_point(input).0;
+				let position = match self.settings.orientation {
+					SliderOrientation::Horizontal => point.x,
+					SliderOrientation::Vertical => point.y,
+				};
+				self.set_value_changed(self.position_to_value(position));
+			}
+		}
+		if self.changed {
+			self.update_visuals();
+		}
+		keys_handled || input_handled
+	}
+}
+impl VisualDebug for Slider {
+	fn set_debug(&mut self, settings: Option<crate::DebugSettings>) {
+		self.touch_plane.set_debug(settings)
+	}
+}
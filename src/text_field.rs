@@ -0,0 +1,427 @@
+use crate::{
+	DebugSettings, UIElement, VisualDebug,
+	dbus::DbusObjectHandles,
+	keyboard::{KeyboardHandler, KeypressInfo, keycodes},
+	lines::{LineExt, line_from_points, rounded_rectangle},
+};
+use glam::vec3;
+use stardust_xr_fusion::{
+	drawable::{Lines, LinesAspect, Text, TextAspect, TextStyle, XAlign, YAlign},
+	fields::{Field, Shape},
+	node::NodeError,
+	objects::zbus::Connection,
+	spatial::{Spatial, SpatialRefAspect, Transform},
+	values::{
+		Vector2,
+		color::{Rgba, color_space::LinearRgb, rgba_linear},
+	},
+};
+use std::{ops::Range, path::Path};
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, Copy)]
+pub struct TextFieldSettings {
+	pub character_height: f32,
+	/// Width of each character relative to its height, the caret assumes a monospace font
+	pub character_width: f32,
+	pub text_color: Rgba<f32, LinearRgb>,
+	pub accent_color: Rgba<f32, LinearRgb>,
+	pub line_thickness: f32,
+	/// None means unlimited
+	pub max_length: Option<usize>,
+}
+impl Default for TextFieldSettings {
+	fn default() -> Self {
+		Self {
+			character_height: 0.01,
+			character_width: 0.6,
+			text_color: rgba_linear!(1.0, 1.0, 1.0, 1.0),
+			accent_color: rgba_linear!(0.0, 1.0, 0.75, 1.0),
+			line_thickness: 0.001,
+			max_length: None,
+		}
+	}
+}
+
+/// A single line of editable text that keyboards can type into.
+///
+/// Positions are in characters, not bytes.
+pub struct TextField {
+	settings: TextFieldSettings,
+	size: Vector2<f32>,
+	root: Spatial,
+	field: Field,
+	text_node: Text,
+	lines: Lines,
+	debug_lines: Option<Lines>,
+	/// Keys with the text they typed, if the keymap is known
+	key_rx: mpsc::UnboundedReceiver<(KeypressInfo, Option<String>)>,
+	_keyboard: DbusObjectHandles,
+
+	text: String,
+	cursor: usize,
+	/// Where the selection started, the cursor is the other end
+	anchor: Option<usize>,
+	/// First character that's visible when the text is too long to fit
+	scroll: usize,
+	shift: u8,
+	ctrl: u8,
+	caps_lock: bool,
+
+	changed: bool,
+	submitted: Option<String>,
+}
+impl TextField {
+	/// The keyboard handler lives on `path`, so send keys there
+	pub fn create(
+		connection: Connection,
+		path: impl AsRef<Path>,
+		parent: &impl SpatialRefAspect,
+		transform: Transform,
+		size: impl Into<Vector2<f32>>,
+		text: &str,
+		settings: TextFieldSettings,
+	) -> Result<Self, NodeError> {
+		let size = size.into();
+		let root = Spatial::create(parent, transform)?;
+		let field = Field::create(
+			&root,
+			Transform::identity(),
+			Shape::Box([size.x, size.y, settings.character_height].into()),
+		)?;
+		let text_node = Text::create(
+			&root,
+			Transform::from_translation([size.x * -0.5 + Self::padding(&settings), 0.0, 0.0]),
+			"",
+			TextStyle {
+				character_height: settings.character_height,
+				color: settings.text_color,
+				text_align_x: XAlign::Left,
+				text_align_y: YAlign::Center,
+				..Default::default()
+			},
+		)?;
+		let lines = Lines::create(&root, Transform::identity(), &[])?;
+
+		let (key_tx, key_rx) = mpsc::unbounded_channel();
+		#[cfg(feature = "xkbcommon")]
+		let keyboard = KeyboardHandler::create_xkb(
+			connection,
+			path,
+			Some(&root),
+			&field,
+			move |key_info, key| {
+				let _ = key_tx.send((key_info, key.map(|key| key.text)));
+			},
+		);
+		#[cfg(not(feature = "xkbcommon"))]
+		let keyboard = KeyboardHandler::create(connection, path, Some(&root), &field, move |key_info| {
+			let _ = key_tx.send((key_info, None));
+		});
+
+		let mut text_field = TextField {
+			settings,
+			size,
+			root,
+			field,
+			text_node,
+			lines,
+			debug_lines: None,
+			key_rx,
+			_keyboard: keyboard,
+			text: String::new(),
+			cursor: 0,
+			anchor: None,
+			scroll: 0,
+			shift: 0,
+			ctrl: 0,
+			caps_lock: false,
+			changed: false,
+			submitted: None,
+		};
+		text_field.set_text(text);
+		Ok(text_field)
+	}
+
+	pub fn text(&self) -> &str {
+		&self.text
+	}
+	/// Moves the cursor to the end and doesn't count as a change, cut off at the max length
+	pub fn set_text(&mut self, text: &str) {
+		self.text = match self.settings.max_length {
+			Some(max_length) => text.chars().take(max_length).collect(),
+			None => text.to_string(),
+		};
+		self.cursor = self.len();
+		self.anchor = None;
+		self.update_visuals();
+	}
+	pub fn cursor(&self) -> usize {
+		self.cursor
+	}
+	pub fn set_cursor(&mut self, cursor: usize) {
+		self.cursor = cursor.min(self.len());
+		self.anchor = None;
+		self.update_visuals();
+	}
+	/// The selected characters, if any
+	pub fn selection(&self) -> Option<Range<usize>> {
+		let anchor = self.anchor?;
+		(anchor != self.cursor).then(|| anchor.min(self.cursor)..anchor.max(self.cursor))
+	}
+	pub fn select(&mut self, range: Range<usize>) {
+		self.anchor = Some(range.start.min(self.len()));
+		self.cursor = range.end.min(self.len());
+		self.update_visuals();
+	}
+	pub fn selected_text(&self) -> Option<&str> {
+		let selection = self.selection()?;
+		Some(&self.text[self.byte_index(selection.start)..self.byte_index(selection.end)])
+	}
+
+	/// The text if it was edited during the last update
+	pub fn changed(&self) -> Option<&str> {
+		self.changed.then_some(self.text.as_str())
+	}
+	/// The text if enter was pressed during the last update
+	pub fn submitted(&self) -> Option<&str> {
+		self.submitted.as_deref()
+	}
+
+	pub fn root(&self) -> &Spatial {
+		&self.root
+	}
+	pub fn field(&self) -> &Field {
+		&self.field
+	}
+
+	fn padding(settings: &TextFieldSettings) -> f32 {
+		settings.character_height * 0.5
+	}
+	fn character_advance(&self) -> f32 {
+		self.settings.character_height * self.settings.character_width
+	}
+	fn visible_characters(&self) -> usize {
+		let width = self.size.x - Self::padding(&self.settings) * 2.0;
+		(width / self.character_advance()).max(1.0) as usize
+	}
+	fn len(&self) -> usize {
+		self.text.chars().count()
+	}
+	fn byte_index(&self, char_index: usize) -> usize {
+		self.text
+			.char_indices()
+			.nth(char_index)
+			.map(|(i, _)| i)
+			.unwrap_or(self.text.len())
+	}
+
+	/// Move the cursor, keeping the selection's anchor when `select` is true
+	fn move_cursor(&mut self, cursor: usize, select: bool) {
+		if select {
+			self.anchor.get_or_insert(self.cursor);
+		} else {
+			self.anchor = None;
+		}
+		self.cursor = cursor.min(self.len());
+	}
+	fn delete_selection(&mut self) -> bool {
+		let Some(selection) = self.selection() else {
+			return false;
+		};
+		let bytes = self.byte_index(selection.start)..self.byte_index(selection.end);
+		self.text.replace_range(bytes, "");
+		self.cursor = selection.start;
+		self.anchor = None;
+		true
+	}
+	fn insert(&mut self, character: char) {
+		// replacing a selection at the max length still deletes it
+		if self.delete_selection() {
+			self.changed = true;
+		}
+		self.anchor = None;
+		if self
+			.settings
+			.max_length
+			.is_some_and(|max_length| self.len() >= max_length)
+		{
+			return;
+		}
+		let index = self.byte_index(self.cursor);
+		self.text.insert(index, character);
+		self.cursor += 1;
+		self.changed = true;
+	}
+
+	fn handle_key(&mut self, key_info: KeypressInfo, text: Option<String>) {
+		let modifier = match key_info.key {
+			keycodes::LEFT_SHIFT | keycodes::RIGHT_SHIFT => Some(&mut self.shift),
+			keycodes::LEFT_CTRL | keycodes::RIGHT_CTRL => Some(&mut self.ctrl),
+			_ => None,
+		};
+		if let Some(modifier) = modifier {
+			*modifier = if key_info.pressed {
+				modifier.saturating_add(1)
+			} else {
+				modifier.saturating_sub(1)
+			};
+			return;
+		}
+		if !key_info.pressed {
+			return;
+		}
+
+		let select = self.shift > 0;
+		match key_info.key {
+			keycodes::CAPS_LOCK => self.caps_lock = !self.caps_lock,
+			keycodes::LEFT => match self.selection() {
+				Some(selection) if !select => self.move_cursor(selection.start, false),
+				_ => self.move_cursor(self.cursor.saturating_sub(1), select),
+			},
+			keycodes::RIGHT => match self.selection() {
+				Some(selection) if !select => self.move_cursor(selection.end, false),
+				_ => self.move_cursor(self.cursor + 1, select),
+			},
+			keycodes::HOME | keycodes::UP => self.move_cursor(0, select),
+			keycodes::END | keycodes::DOWN => self.move_cursor(self.len(), select),
+			keycodes::BACKSPACE => {
+				if self.delete_selection() {
+					self.changed = true;
+				} else if self.cursor > 0 {
+					self.cursor -= 1;
+					let index = self.byte_index(self.cursor);
+					self.text.remove(index);
+					self.changed = true;
+				}
+			}
+			keycodes::DELETE => {
+				if self.delete_selection() {
+					self.changed = true;
+				} else if self.cursor < self.len() {
+					let index = self.byte_index(self.cursor);
+					self.text.remove(index);
+					self.changed = true;
+				}
+			}
+			keycodes::ENTER | keycodes::KP_ENTER => self.submitted = Some(self.text.clone()),
+			// ctrl+a selects everything
+			keycodes::A if self.ctrl > 0 => {
+				self.anchor = Some(0);
+				self.cursor = self.len();
+			}
+			key if self.ctrl == 0 => match text {
+				Some(text) => text
+					.chars()
+					.filter(|character| !character.is_control())
+					.for_each(|character| self.insert(character)),
+				None => {
+					if let Some(character) =
+						us_layout_character(key, self.shift > 0, self.caps_lock)
+					{
+						self.insert(character);
+					}
+				}
+			},
+			_ => (),
+		}
+	}
+
+	fn update_visuals(&mut self) {
+		// keep the cursor in view
+		let visible = self.visible_characters();
+		if self.cursor < self.scroll {
+			self.scroll = self.cursor;
+		} else if self.cursor > self.scroll + visible {
+			self.scroll = self.cursor - visible;
+		}
+		self.scroll = self.scroll.min(self.len());
+		let shown: String = self.text.chars().skip(self.scroll).take(visible).collect();
+		let _ = self.text_node.set_text(&shown);
+
+		let left = self.size.x * -0.5 + Self::padding(&self.settings);
+		let advance = self.character_advance();
+		let x =
+			|index: usize| left + (index.saturating_sub(self.scroll)).min(visible) as f32 * advance;
+		let half_height = self.settings.character_height * 0.6;
+
+		let caret = line_from_points(vec![
+			vec3(x(self.cursor), -half_height, 0.0),
+			vec3(x(self.cursor), half_height, 0.0),
+		])
+		.color(self.settings.accent_color);
+		let mut lines = vec![caret];
+		if let Some(selection) = self.selection() {
+			let underline = line_from_points(vec![
+				vec3(x(selection.start), -half_height, 0.0),
+				vec3(x(selection.end), -half_height, 0.0),
+			])
+			.color(self.settings.accent_color);
+			lines.push(underline);
+		}
+		let _ = self.lines.set_lines(
+			&lines
+				.into_iter()
+				.map(|line| line.thickness(self.settings.line_thickness))
+				.collect::<Vec<_>>(),
+		);
+	}
+}
+impl UIElement for TextField {
+	fn handle_events(&mut self) -> bool {
+		self.changed = false;
+		self.submitted = None;
+		let mut handled = false;
+		while let Ok((key_info, text)) = self.key_rx.try_recv() {
+			self.handle_key(key_info, text);
+			handled = true;
+		}
+		if handled {
+			self.update_visuals();
+		}
+		handled
+	}
+}
+impl VisualDebug for TextField {
+	fn set_debug(&mut self, settings: Option<DebugSettings>) {
+		self.debug_lines = settings.and_then(|settings| {
+			let outline =
+				rounded_rectangle(self.size.x, self.size.y, settings.line_thickness * 0.5, 4)
+					.thickness(settings.line_thickness)
+					.color(settings.line_color);
+			Lines::create(&self.root, Transform::identity(), &[outline]).ok()
+		})
+	}
+}
+
+/// Fallback for turning evdev keycodes into text when the keymap isn't known
+fn us_layout_character(key: u32, shift: bool, caps_lock: bool) -> Option<char> {
+	const ROWS: [(u32, &str, &str); 4] = [
+		(2, "1234567890-=", "!@#$%^&*()_+"),
+		(16, "qwertyuiop[]", "QWERTYUIOP{}"),
+		(30, "asdfghjkl;'`", "ASDFGHJKL:\"~"),
+		(43, "\\zxcvbnm,./", "|ZXCVBNM<>?"),
+	];
+	if key == 57 {
+		return Some(' ');
+	}
+	ROWS.iter().find_map(|(start, lower, upper)| {
+		let index = key.checked_sub(*start)? as usize;
+		let lower = lower.chars().nth(index)?;
+		let upper = upper.chars().nth(index)?;
+		// caps lock only affects letters
+		let shift = shift ^ (caps_lock && lower.is_ascii_alphabetic());
+		Some(if shift { upper } else { lower })
+	})
+}
+
+#[test]
+fn us_layout() {
+	assert_eq!(us_layout_character(keycodes::A, false, false), Some('a'));
+	assert_eq!(us_layout_character(keycodes::A, true, false), Some('A'));
+	assert_eq!(us_layout_character(keycodes::A, true, true), Some('a'));
+	assert_eq!(us_layout_character(2, false, true), Some('1'));
+	assert_eq!(us_layout_character(53, true, false), Some('?'));
+	assert_eq!(us_layout_character(57, false, false), Some(' '));
+	assert_eq!(us_layout_character(keycodes::ENTER, false, false), None);
+}
@@ -0,0 +1,208 @@
+use crate::{
+	UIElement, VisualDebug,
+	button::{Button, ButtonSettings, ToggleStyle},
+	touch_plane::TouchPlane,
+};
+use stardust_xr_fusion::{
+	node::NodeError,
+	spatial::{SpatialRefAspect, Transform},
+	values::Vector2,
+};
+
+/// A button that switches on and off each time it's released.
+pub struct ToggleButton {
+	button: Button,
+	style: ToggleStyle,
+	on: bool,
+	changed: bool,
+}
+impl ToggleButton {
+	pub fn create(
+		parent: &impl SpatialRefAspect,
+		transform: Transform,
+		size: impl Into<Vector2<f32>>,
+		on: bool,
+		settings: ButtonSettings,
+	) -> Result<Self, NodeError> {
+		Self::create_styled(parent, transform, size, on, settings, ToggleStyle::Fill)
+	}
+	fn create_styled(
+		parent: &impl SpatialRefAspect,
+		transform: Transform,
+		size: impl Into<Vector2<f32>>,
+		on: bool,
+		settings: ButtonSettings,
+		style: ToggleStyle,
+	) -> Result<Self, NodeError> {
+		let mut button = Button::create(parent, transform, size, settings)?;
+		button.set_toggled(Some((style, on)));
+		Ok(ToggleButton {
+			button,
+			style,
+			on,
+			changed: false,
+		})
+	}
+
+	pub fn on(&self) -> bool {
+		self.on
+	}
+	/// Doesn't count as a change
+	pub fn set_on(&mut self, on: bool) {
+		self.on = on;
+		self.button.set_toggled(Some((self.style, on)));
+	}
+	/// The new state if it was toggled during the last update
+	pub fn changed(&self) -> Option<bool> {
+		self.changed.then_some(self.on)
+	}
+
+	pub fn button(&self) -> &Button {
+		&self.button
+	}
+	pub fn touch_plane(&self) -> &TouchPlane {
+		self.button.touch_plane()
+	}
+}
+impl UIElement for ToggleButton {
+	fn handle_events(&mut self) -> bool {
+		self.changed = false;
+		if !self.button.handle_events() {
+			return false;
+		}
+		if self.button.released() {
+			self.changed = true;
+			self.set_on(!self.on);
+		}
+		true
+	}
+}
+impl VisualDebug for ToggleButton {
+	fn set_debug(&mut self, settings: Option<crate::DebugSettings>) {
+		self.button.set_debug(settings)
+	}
+}
+
+/// A square toggle with a check mark.
+pub struct Checkbox(ToggleButton);
+impl Checkbox {
+	pub fn create(
+		parent: &impl SpatialRefAspect,
+		transform: Transform,
+		size: f32,
+		checked: bool,
+		settings: ButtonSettings,
+	) -> Result<Self, NodeError> {
+		Ok(Checkbox(ToggleButton::create_styled(
+			parent,
+			transform,
+			[size; 2],
+			checked,
+			settings,
+			ToggleStyle::Check,
+		)?))
+	}
+
+	pub fn checked(&self) -> bool {
+		self.0.on()
+	}
+	/// Doesn't count as a change
+	pub fn set_checked(&mut self, checked: bool) {
+		self.0.set_on(checked)
+	}
+	/// The new state if it was checked or unchecked during the last update
+	pub fn changed(&self) -> Option<bool> {
+		self.0.changed()
+	}
+	pub fn toggle(&self) -> &ToggleButton {
+		&self.0
+	}
+}
+impl UIElement for Checkbox {
+	fn handle_events(&mut self) -> bool {
+		self.0.handle_events()
+	}
+}
+impl VisualDebug for Checkbox {
+	fn set_debug(&mut self, settings: Option<crate::DebugSettings>) {
+		self.0.set_debug(settings)
+	}
+}
+
+/// Toggles where exactly one is on at a time, the first option added starts selected.
+#[derive(Default)]
+pub struct RadioGroup {
+	options: Vec<ToggleButton>,
+	selected: Option<usize>,
+	changed: bool,
+}
+impl RadioGroup {
+	/// Add an option and return its index, the first option added starts selected
+	pub fn add(
+		&mut self,
+		parent: &impl SpatialRefAspect,
+		transform: Transform,
+		size: impl Into<Vector2<f32>>,
+		settings: ButtonSettings,
+	) -> Result<usize, NodeError> {
+		let selected = self.selected.is_none();
+		let option = ToggleButton::create(parent, transform, size, selected, settings)?;
+		self.options.push(option);
+		let index = self.options.len() - 1;
+		if selected {
+			self.selected = Some(index);
+		}
+		Ok(index)
+	}
+
+	/// Only `None` before any options are added
+	pub fn selected(&self) -> Option<usize> {
+		self.selected
+	}
+	/// Doesn't count as a change
+	pub fn select(&mut self, index: usize) {
+		if index >= self.options.len() {
+			return;
+		}
+		self.selected = Some(index);
+		for (i, option) in self.options.iter_mut().enumerate() {
+			option.set_on(i == index);
+		}
+	}
+	/// The newly selected option if the selection changed during the last update
+	pub fn changed(&self) -> Option<usize> {
+		self.changed.then_some(self.selected).flatten()
+	}
+
+	pub fn options(&self) -> &[ToggleButton] {
+		&self.options
+	}
+}
+impl UIElement for RadioGroup {
+	fn handle_events(&mut self) -> bool {
+		self.changed = false;
+		let mut handled = false;
+		let mut newly_selected = None;
+		for (i, option) in self.options.iter_mut().enumerate() {
+			handled |= option.handle_events();
+			match option.changed() {
+				Some(true) => newly_selected = Some(i),
+				// the selected option can't be turned off by pressing it again
+				Some(false) => option.set_on(true),
+				None => (),
+			}
+		}
+		if let Some(index) = newly_selected {
+			self.changed = self.selected != Some(index);
+			self.select(index);
+		}
+		handled
+	}
+}
+impl VisualDebug for RadioGroup {
+	fn set_debug(&mut self, settings: Option<crate::DebugSettings>) {
+		for option in &mut self.options {
+			option.set_debug(settings);
+		}
+	}
+}
@@ -1,6 +1,9 @@
 use crate::{
 	DebugSettings, UIElement, VisualDebug,
-	input_action::{InputQueue, InputQueueable, MultiAction},
+	input_action::{
+		Gesture, GestureRecognizer, GestureSettings, InputQueue, InputQueueable, InteractCondition,
+		InteractConditionSettings, InteractThreshold, MultiAction,
+	},
 	lines::{self, LineExt},
 };
 use glam::{Mat4, Vec3, vec3};
@@ -20,11 +23,16 @@ pub struct TouchPlane {
 	pub x_range: Range<f32>,
 	pub y_range: Range<f32>,
 	thickness: f32,
+	pub gesture_settings: GestureSettings,
+	/// Only pointers use this, hands and tips interact by pushing through the plane
+	pub interact_settings: InteractConditionSettings,
 
 	root: Spatial,
 	input: InputQueue,
 	field: Field,
+	interact_condition: InteractCondition,
 	action: MultiAction,
+	gestures: GestureRecognizer,
 
 	debug_lines: Option<Lines>,
 }
@@ -51,11 +59,22 @@ impl TouchPlane {
 			x_range,
 			y_range,
 			thickness,
+			gesture_settings: GestureSettings::default(),
+			interact_settings: InteractConditionSettings {
+				pointer: InteractThreshold {
+					key: "select",
+					engage: 0.5,
+					release: 0.4,
+				},
+				..InteractConditionSettings::select()
+			},
 
 			root,
 			input,
 			field,
+			interact_condition: InteractCondition::default(),
 			action: Default::default(),
+			gestures: Default::default(),
 			debug_lines: None,
 		})
 	}
@@ -66,17 +85,20 @@ impl TouchPlane {
 			&& point.y.abs() * 2.0 < size.y
 	}
 
-	pub fn interact_point(&self, input: &InputData) -> (Vector2<f32>, f32) {
-		let interact_point = match &input.input {
+	pub fn interact_point_local(input: &InputData) -> Vec3 {
+		match &input.input {
 			InputDataType::Pointer(p) => {
 				let normal = vec3(0.0, 0.0, 1.0);
 				let denom = normal.dot(p.direction().into());
 				let t = -Vec3::from(p.origin).dot(normal) / denom;
-				(Vec3::from(p.origin) + Vec3::from(p.direction()) * t).into()
+				Vec3::from(p.origin) + Vec3::from(p.direction()) * t
 			}
-			InputDataType::Hand(h) => h.index.tip.position,
-			InputDataType::Tip(t) => t.origin,
-		};
+			InputDataType::Hand(h) => h.index.tip.position.into(),
+			InputDataType::Tip(t) => t.origin.into(),
+		}
+	}
+	pub fn interact_point(&self, input: &InputData) -> (Vector2<f32>, f32) {
+		let interact_point = Self::interact_point_local(input);
 
 		let x = interact_point
 			.x
@@ -102,9 +124,20 @@ impl TouchPlane {
 	pub fn field(&self) -> &Field {
 		&self.field
 	}
+	pub fn input_queue(&self) -> &InputQueue {
+		&self.input
+	}
+	/// Mainly useful for recording or replaying input
+	pub fn input_queue_mut(&mut self) -> &mut InputQueue {
+		&mut self.input
+	}
 	pub fn action(&self) -> &MultiAction {
 		&self.action
 	}
+	/// Gestures from the last update, positions are local to the root
+	pub fn gestures(&self) -> &[Gesture] {
+		self.gestures.gestures()
+	}
 
 	pub fn set_size(&mut self, size: impl Into<Vector2<f32>>) -> Result<(), NodeError> {
 		let size = size.into();
@@ -129,9 +162,13 @@ impl TouchPlane {
 }
 impl UIElement for TouchPlane {
 	fn handle_events(&mut self) -> bool {
-		if !self.input.handle_events() {
+		// pointers held past the hold time engage without any new input events
+		let hold_elapsed = self.interact_condition.update_hold(&self.interact_settings);
+		if !self.input.handle_events() && !hold_elapsed {
 			return false;
 		}
+		self.interact_condition
+			.update(&self.input, &self.interact_settings);
 		self.action.update(
 			&self.input,
 			|input| match &input.input {
@@ -140,13 +177,16 @@ impl UIElement for TouchPlane {
 				InputDataType::Tip(t) => Self::hover(self.size, t.origin, true),
 			},
 			|input| match &input.input {
-				InputDataType::Pointer(_) => {
-					input.datamap.with_data(|d| d.idx("select").as_f32() > 0.5)
-				}
+				InputDataType::Pointer(_) => self.interact_condition.engaged(input),
 				InputDataType::Hand(h) => Self::hover(self.size, h.index.tip.position, false),
 				InputDataType::Tip(t) => Self::hover(self.size, t.origin, false),
 			},
 		);
+		self.gestures.update_multi(
+			&self.action,
+			&self.gesture_settings,
+			Self::interact_point_local,
+		);
 		true
 	}
 }
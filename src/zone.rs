@@ -23,6 +23,13 @@ impl Zone {
 	pub fn new_with_margin(field: Field, margin: f32) -> Self {
 		Zone { field, margin }
 	}
+	pub fn field(&self) -> &Field {
+		&self.field
+	}
+	/// How far outside the field something can be and still be in the zone
+	pub fn margin(&self) -> f32 {
+		self.margin
+	}
 }
 
 pub struct Zoneable {